@@ -0,0 +1,817 @@
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box, sync::Arc, vec::Vec};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+use core::ptr::NonNull;
+use core::{fmt, hash, mem, ops};
+#[cfg(feature = "alloc")]
+use core::{iter, slice};
+
+#[cfg(feature = "alloc")]
+use crate::header;
+use crate::{Buf16, Buf24, Repr, TryReserveError};
+
+/// A small-data optimized byte buffer.
+///
+/// See [`Str8`](crate::Str8) for all the properties.
+#[repr(transparent)]
+pub struct Buf8(Buf8Inner);
+
+pub(crate) const INLINE_CAP: usize = 7;
+
+/// Inline buffers overwrite the pointer with their bytes. It is a real pointer nonetheless,
+/// so that it keeps its provenance while the tag is stored in its top byte.
+///
+/// This needs 8-byte pointers, as smaller ones would leave the tag byte of the inline
+/// layout in the padding.
+#[repr(C, align(8))]
+#[derive(Clone, Copy, PartialEq)]
+struct Buf8Inner {
+    ptr_with_tag: NonNull<u8>,
+}
+
+/// The tag is stored in the byte which holds the top byte of the pointer,
+/// which comes last on little-endian targets, and first on big-endian ones.
+#[cfg(target_endian = "little")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Buf8Inline {
+    buf: [u8; INLINE_CAP],
+    tag_and_len: u8,
+}
+
+#[cfg(target_endian = "big")]
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Buf8Inline {
+    tag_and_len: u8,
+    buf: [u8; INLINE_CAP],
+}
+
+const _: () = {
+    assert!(mem::size_of::<Buf8>() == 8);
+    assert!(mem::align_of::<Buf8>() == 8);
+    assert!(mem::size_of::<Option<Buf8>>() == 8);
+
+    assert!(mem::size_of::<Buf8Inline>() == mem::size_of::<Buf8Inner>());
+    assert!(mem::size_of::<NonNull<u8>>() == mem::size_of::<Buf8Inner>());
+};
+
+const TAG_INLINE: u8 = 0b001 << 5;
+/// An allocation created by [`header::try_alloc`], which holds the length in its header.
+const TAG_ARC: u8 = 0b010 << 5;
+/// A pointer to a `&'static [u8]`, which holds the length.
+const TAG_STATIC: u8 = 0b100 << 5;
+const TAG_MASK: u8 = !(0b111 << 5);
+/// The pointer lives in the bits not taken by the tag.
+const PTR_MASK: usize = !(0b111 << (usize::BITS - 3));
+const TAG_SHIFT: u32 = usize::BITS - 8;
+
+// The pointer is either `'static`, or refers to a thread-safe, reference-counted allocation.
+unsafe impl Send for Buf8 {}
+unsafe impl Sync for Buf8 {}
+
+impl Buf8 {
+    /// Constructs inline variant of `Buf8`.
+    ///
+    /// Panics if `input.len() > 7`.
+    #[inline]
+    pub const fn new_inline(input: &[u8]) -> Self {
+        let len = input.len();
+        assert!(len <= INLINE_CAP); // avoids checks in loop
+
+        let mut buf = [0; INLINE_CAP];
+
+        let mut i = 0;
+        while i < len {
+            buf[i] = input[i];
+            i += 1
+        }
+
+        let tag_and_len = len as u8 | TAG_INLINE;
+        unsafe { mem::transmute(Buf8Inline { buf, tag_and_len }) }
+    }
+
+    /// Constructs a `Buf8` from a reference to a statically allocated slice.
+    ///
+    /// A `Buf8` has no room for a length next to its pointer, so it points to the
+    /// `&'static [u8]` itself instead of the bytes. This never allocates.
+    #[inline]
+    pub fn new_static(input: &'static &'static [u8]) -> Self {
+        if input.len() <= INLINE_CAP {
+            Self::new_inline(input)
+        } else {
+            Self::from_tagged_ptr(input as *const &[u8] as *const u8, TAG_STATIC)
+        }
+    }
+
+    /// Constructs a `Buf8`, allocating `input` on the heap if it does not fit inline.
+    ///
+    /// Panics or aborts like `Vec` if the allocation fails.
+    #[cfg(feature = "alloc")]
+    pub fn new(input: &[u8]) -> Self {
+        Self::try_new(input).unwrap_or_else(|err| err.handle())
+    }
+
+    /// Constructs a `Buf8`, returning an error if `input` does not fit inline,
+    /// and can not be allocated on the heap.
+    ///
+    /// Without the `alloc` feature, this fails for every `input` longer than 7 bytes.
+    #[inline]
+    pub fn try_new(input: &[u8]) -> Result<Self, TryReserveError> {
+        if input.len() <= INLINE_CAP {
+            return Ok(Self::new_inline(input));
+        }
+        #[cfg(feature = "alloc")]
+        return header::try_alloc(input, None).map(Self::from_header);
+        #[cfg(not(feature = "alloc"))]
+        Err(TryReserveError::disabled(input.len()))
+    }
+
+    /// Collects the bytes of `iter` into a `Buf8`, returning an error instead of
+    /// aborting if the bytes do not fit inline, and can not be allocated on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn try_from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Result<Self, TryReserveError> {
+        let mut iter = iter.into_iter();
+        let mut buf = [0; INLINE_CAP];
+        let mut len = 0;
+        while let Some(byte) = iter.next() {
+            if len == INLINE_CAP {
+                let mut heap = Vec::new();
+                header::try_extend(&mut heap, &buf)?;
+                header::try_extend(&mut heap, &[byte])?;
+                for byte in iter {
+                    header::try_extend(&mut heap, &[byte])?;
+                }
+                return Self::try_new(&heap);
+            }
+            buf[len] = byte;
+            len += 1;
+        }
+        Ok(Self::new_inline(&buf[..len]))
+    }
+
+    /// Takes over the reference to `data`, which was created by [`header::try_alloc`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn from_header(data: NonNull<u8>) -> Self {
+        Self::from_tagged_ptr(data.as_ptr(), TAG_ARC)
+    }
+
+    /// Decomposes a heap-allocated buffer into its data pointer.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_header(self) -> Result<NonNull<u8>, Self> {
+        let Some(data) = self.header_data() else {
+            return Err(self);
+        };
+        mem::forget(self);
+        Ok(unsafe { NonNull::new_unchecked(data as *mut u8) })
+    }
+
+    #[inline]
+    fn from_tagged_ptr(ptr: *const u8, tag: u8) -> Self {
+        assert!(
+            ptr.addr() & PTR_MASK == ptr.addr(),
+            "pointer does not fit next to the tag"
+        );
+        let ptr_with_tag = ptr.map_addr(|addr| addr | (tag as usize) << TAG_SHIFT);
+        let ptr_with_tag = unsafe { NonNull::new_unchecked(ptr_with_tag as *mut u8) };
+        Self(Buf8Inner { ptr_with_tag })
+    }
+
+    #[inline(always)]
+    fn ptr(&self) -> *const u8 {
+        let ptr_with_tag = self.0.ptr_with_tag.as_ptr() as *const u8;
+        ptr_with_tag.map_addr(|addr| addr & PTR_MASK)
+    }
+
+    /// Returns the pointer to the bytes of an allocation created by [`header::try_alloc`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn header_data(&self) -> Option<*const u8> {
+        if self.tag_byte() & !TAG_MASK != TAG_ARC {
+            return None;
+        }
+        Some(self.ptr())
+    }
+
+    /// Returns the slice pointed to by a buffer created by [`Buf8::new_static`].
+    #[inline(always)]
+    fn static_bytes(&self) -> &'static [u8] {
+        unsafe { *(self.ptr() as *const &'static [u8]) }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_byte_iter<I: iter::Iterator<Item = u8>>(mut iter: I) -> Buf8 {
+        let (min_size, _) = iter.size_hint();
+        if min_size > INLINE_CAP {
+            let heap: Vec<u8> = iter.collect();
+            return Self::new(&heap);
+        }
+        let mut len = 0;
+        let mut buf = [0u8; INLINE_CAP];
+        while let Some(byte) = iter.next() {
+            if len == INLINE_CAP {
+                let (min_remaining, _) = iter.size_hint();
+                let mut heap = Vec::with_capacity(len + 1 + min_remaining);
+                heap.extend_from_slice(&buf);
+                heap.push(byte);
+                heap.extend(iter);
+                return Self::new(&heap);
+            }
+            buf[len] = byte;
+            len += 1;
+        }
+        Self::new_inline(&buf[..len])
+    }
+
+    /// Returns a sub-slice of this buffer.
+    ///
+    /// Slices that fit are stored inline. Longer slices are copied into a new
+    /// heap allocation, as a `Buf8` has no room to store an offset.
+    ///
+    /// Panics if the range is out of bounds.
+    #[cfg(feature = "alloc")]
+    pub fn slice(&self, range: impl ops::RangeBounds<usize>) -> Self {
+        let bytes = self.as_bytes();
+        let sub = &bytes[(range.start_bound().cloned(), range.end_bound().cloned())];
+        if sub.len() == bytes.len() {
+            return self.clone();
+        }
+        Self::new(sub)
+    }
+
+    #[inline(always)]
+    fn tag_byte(&self) -> u8 {
+        self.inline().tag_and_len
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.as_bytes().len()
+    }
+
+    #[inline(always)]
+    pub fn is_heap_allocated(&self) -> bool {
+        self.tag_byte() & TAG_ARC > 0
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how the contents of this buffer are stored.
+    #[inline]
+    pub fn kind(&self) -> Repr {
+        match self.tag_byte() & !TAG_MASK {
+            TAG_INLINE => Repr::Inline,
+            TAG_ARC => Repr::Heap,
+            _ => Repr::Static,
+        }
+    }
+
+    /// Returns the number of values sharing the heap allocation of this buffer,
+    /// or `None` if it is not heap-allocated.
+    #[inline]
+    pub fn strong_count(&self) -> Option<usize> {
+        #[cfg(feature = "alloc")]
+        return self
+            .header_data()
+            .map(|data| unsafe { header::strong_count(data) });
+        #[cfg(not(feature = "alloc"))]
+        None
+    }
+
+    /// Returns `true` if both buffers refer to the same non-inline memory.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Buf8) -> bool {
+        self.kind() != Repr::Inline && self.0 == other.0
+    }
+
+    /// Returns the underlying `&'static` slice, if this buffer was created from one.
+    ///
+    /// This returns `None` for static slices that were short enough to be stored inline.
+    #[inline]
+    pub fn as_static(&self) -> Option<&'static [u8]> {
+        if self.kind() != Repr::Static {
+            return None;
+        }
+        Some(self.static_bytes())
+    }
+
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        let tag_byte = self.tag_byte();
+        match tag_byte & !TAG_MASK {
+            TAG_INLINE => {
+                let len = (tag_byte & TAG_MASK) as usize;
+                &self.inline().buf[..len]
+            }
+            #[cfg(feature = "alloc")]
+            TAG_ARC => {
+                let data = self.ptr();
+                unsafe { slice::from_raw_parts(data, header::len(data)) }
+            }
+            _ => self.static_bytes(),
+        }
+    }
+
+    /// Returns a mutable slice of the bytes of this buffer.
+    ///
+    /// Inline buffers and heap buffers which are not shared are mutated in place.
+    /// Static and shared buffers are copied into a new heap allocation first.
+    #[cfg(feature = "alloc")]
+    pub fn make_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        if self.tag_byte() & !TAG_MASK == TAG_INLINE {
+            return &mut self.inline_mut().buf[..len];
+        }
+
+        let is_unique = self
+            .header_data()
+            .is_some_and(|data| unsafe { header::make_mut(data) });
+        if !is_unique {
+            *self = Self::from_parts(&[self.as_bytes()]);
+        }
+        unsafe { slice::from_raw_parts_mut(self.ptr() as *mut u8, len) }
+    }
+
+    /// Appends a byte to the end of the buffer.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn push(&mut self, byte: u8) {
+        self.extend_from_slice(&[byte]);
+    }
+
+    /// Appends the given bytes to the end of the buffer.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.insert_slice(self.len(), bytes);
+    }
+
+    /// Inserts the given bytes at position `idx`.
+    ///
+    /// This happens in place if the result still fits inline.
+    /// Otherwise, the result is copied into a new heap allocation.
+    ///
+    /// Panics if `idx > len`.
+    #[cfg(feature = "alloc")]
+    pub fn insert_slice(&mut self, idx: usize, bytes: &[u8]) {
+        let len = self.len();
+        assert!(
+            idx <= len,
+            "insertion index (is {idx}) should be <= len (is {len})"
+        );
+        if bytes.is_empty() {
+            return;
+        }
+
+        let new_len = len + bytes.len();
+        if new_len <= INLINE_CAP {
+            // only inline buffers can be this short
+            let inline = self.inline_mut();
+            inline.buf.copy_within(idx..len, idx + bytes.len());
+            inline.buf[idx..][..bytes.len()].copy_from_slice(bytes);
+            inline.tag_and_len = new_len as u8 | TAG_INLINE;
+            return;
+        }
+
+        let current = self.as_bytes();
+        *self = Self::from_parts(&[&current[..idx], bytes, &current[idx..]]);
+    }
+
+    /// Shortens the buffer to `new_len` bytes.
+    ///
+    /// A longer heap-allocated result is copied, see [`Buf8::slice`].
+    /// This has no effect if `new_len` is greater than the current length.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            *self = self.slice(..new_len);
+        }
+    }
+
+    /// Truncates the buffer to an empty one.
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Concatenates the given parts into a new buffer.
+    #[cfg(feature = "alloc")]
+    #[cold]
+    fn from_parts(parts: &[&[u8]]) -> Self {
+        let len = parts.iter().map(|part| part.len()).sum();
+        if len <= INLINE_CAP {
+            let mut buf = [0; INLINE_CAP];
+            let mut offset = 0;
+            for part in parts {
+                buf[offset..][..part.len()].copy_from_slice(part);
+                offset += part.len();
+            }
+            return Self::new_inline(&buf[..len]);
+        }
+
+        let mut heap = Vec::with_capacity(len);
+        for part in parts {
+            heap.extend_from_slice(part);
+        }
+        Self::new(&heap)
+    }
+
+    #[inline(always)]
+    fn inline(&self) -> &Buf8Inline {
+        unsafe { mem::transmute::<&Buf8, &Buf8Inline>(self) }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn inline_mut(&mut self) -> &mut Buf8Inline {
+        unsafe { mem::transmute::<&mut Buf8, &mut Buf8Inline>(self) }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for Buf8 {
+    fn drop(&mut self) {
+        if let Some(data) = self.header_data() {
+            unsafe { header::release(data) };
+        }
+    }
+}
+
+impl Clone for Buf8 {
+    fn clone(&self) -> Self {
+        #[cfg(feature = "alloc")]
+        if let Some(data) = self.header_data() {
+            unsafe { header::retain(data) };
+        }
+
+        Self(self.0)
+    }
+}
+
+impl PartialEq for Buf8 {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0 || self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl Eq for Buf8 {}
+
+impl Default for Buf8 {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new_inline(&[])
+    }
+}
+
+impl ops::Deref for Buf8 {
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl PartialEq<[u8]> for Buf8 {
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_bytes() == other
+    }
+}
+
+impl PartialEq<Buf8> for [u8] {
+    fn eq(&self, other: &Buf8) -> bool {
+        other == self
+    }
+}
+
+impl<'a> PartialEq<&'a [u8]> for Buf8 {
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<Buf8> for &[u8] {
+    fn eq(&self, other: &Buf8) -> bool {
+        *self == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<Vec<u8>> for Buf8 {
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_bytes() == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<Buf8> for Vec<u8> {
+    fn eq(&self, other: &Buf8) -> bool {
+        other == self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> PartialEq<&'a Vec<u8>> for Buf8 {
+    fn eq(&self, other: &&'a Vec<u8>) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<Buf8> for &Vec<u8> {
+    fn eq(&self, other: &Buf8) -> bool {
+        *self == other
+    }
+}
+
+impl Ord for Buf8 {
+    fn cmp(&self, other: &Buf8) -> Ordering {
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl PartialOrd for Buf8 {
+    fn partial_cmp(&self, other: &Buf8) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl hash::Hash for Buf8 {
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        self.as_bytes().hash(hasher);
+    }
+}
+
+impl fmt::Debug for Buf8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_bytes(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl iter::FromIterator<u8> for Buf8 {
+    fn from_iter<I: iter::IntoIterator<Item = u8>>(iter: I) -> Buf8 {
+        Self::from_byte_iter(iter.into_iter())
+    }
+}
+
+impl AsRef<[u8]> for Buf8 {
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&[u8]> for Buf8 {
+    #[inline]
+    fn from(b: &[u8]) -> Buf8 {
+        Buf8::new(b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&mut [u8]> for Buf8 {
+    #[inline]
+    fn from(b: &mut [u8]) -> Buf8 {
+        Buf8::new(b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&Vec<u8>> for Buf8 {
+    #[inline]
+    fn from(b: &Vec<u8>) -> Buf8 {
+        Buf8::new(b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Vec<u8>> for Buf8 {
+    #[inline(always)]
+    fn from(b: Vec<u8>) -> Self {
+        Self::new(&b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Box<[u8]>> for Buf8 {
+    #[inline]
+    fn from(b: Box<[u8]>) -> Buf8 {
+        Buf8::new(&b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Arc<[u8]>> for Buf8 {
+    #[inline]
+    fn from(b: Arc<[u8]>) -> Buf8 {
+        Buf8::new(&b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<Cow<'a, [u8]>> for Buf8 {
+    #[inline]
+    fn from(b: Cow<'a, [u8]>) -> Buf8 {
+        Buf8::new(&b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Buf8> for Arc<[u8]> {
+    #[inline(always)]
+    fn from(buf: Buf8) -> Self {
+        Arc::from(buf.as_bytes())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Buf8> for Vec<u8> {
+    #[inline(always)]
+    fn from(buf: Buf8) -> Self {
+        buf.as_bytes().into()
+    }
+}
+
+impl Borrow<[u8]> for Buf8 {
+    #[inline(always)]
+    fn borrow(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl From<Buf8> for Buf16 {
+    /// Converts a [`Buf8`] into a [`Buf16`], moving over its heap allocation.
+    ///
+    /// Bytes which fit inline into a [`Buf16`] are copied, and the allocation is released.
+    #[inline]
+    fn from(buf: Buf8) -> Self {
+        if let Some(bytes) = buf.as_static() {
+            return Self::new_static(bytes);
+        }
+        #[cfg(feature = "alloc")]
+        let buf = match buf.into_header() {
+            Ok(data) => return Self::from_header(data),
+            Err(buf) => buf,
+        };
+        Self::new_inline(&buf)
+    }
+}
+
+impl From<Buf8> for Buf24 {
+    /// Converts a [`Buf8`] into a [`Buf24`], moving over its heap allocation.
+    ///
+    /// Bytes which fit inline into a [`Buf24`] are copied, and the allocation is released.
+    #[inline]
+    fn from(buf: Buf8) -> Self {
+        if let Some(bytes) = buf.as_static() {
+            return Self::new_static(bytes);
+        }
+        #[cfg(feature = "alloc")]
+        let buf = match buf.into_header() {
+            Ok(data) => return Self::from_header(data),
+            Err(buf) => buf,
+        };
+        Self::new_inline(&buf)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Buf16> for Buf8 {
+    /// Converts a [`Buf16`] into a [`Buf8`].
+    ///
+    /// Buffers created by [`Buf16::try_new`] have their allocation moved over,
+    /// all other buffers which do not fit inline are copied.
+    #[inline]
+    fn from(buf: Buf16) -> Self {
+        match buf.into_header() {
+            Ok(data) => Self::from_header(data),
+            Err(buf) => Self::new(&buf),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Buf24> for Buf8 {
+    /// Converts a [`Buf24`] into a [`Buf8`].
+    ///
+    /// Buffers created by [`Buf24::try_new`] or [`Buf24::new_in`] have their allocation
+    /// moved over, all other buffers which do not fit inline are copied.
+    #[inline]
+    fn from(buf: Buf24) -> Self {
+        match buf.into_header() {
+            Ok(data) => Self::from_header(data),
+            Err(buf) => Self::new(&buf),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Buf8 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> Result<Self, arbitrary::Error> {
+        let b = <&[u8]>::arbitrary(u)?;
+        Buf8::try_new(b).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "alloc")]
+    use core::fmt;
+
+    #[cfg(feature = "alloc")]
+    use serde::de::{Deserializer, Error, SeqAccess, Visitor};
+
+    use super::Buf8;
+    #[cfg(feature = "alloc")]
+    use super::INLINE_CAP;
+
+    #[cfg(feature = "alloc")]
+    fn buf8<'de: 'a, 'a, D>(deserializer: D) -> Result<Buf8, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Buf8Visitor;
+
+        impl<'a> Visitor<'a> for Buf8Visitor {
+            type Value = Buf8;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte array")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Buf8::from(v))
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'a [u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Buf8::from(v))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Buf8::from(v))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'a>,
+            {
+                let mut len = 0;
+                let mut buf = [0u8; INLINE_CAP];
+                while let Some(byte) = seq.next_element()? {
+                    if len == INLINE_CAP {
+                        let mut heap = Vec::with_capacity(2 * INLINE_CAP);
+                        heap.extend_from_slice(&buf);
+                        heap.push(byte);
+                        while let Some(byte) = seq.next_element()? {
+                            heap.push(byte);
+                        }
+                        return Ok(Buf8::from(heap));
+                    }
+                    buf[len] = byte;
+                    len += 1;
+                }
+                Ok(Buf8::new_inline(&buf[..len]))
+            }
+        }
+
+        deserializer.deserialize_bytes(Buf8Visitor)
+    }
+
+    impl serde::Serialize for Buf8 {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_bytes(self.as_bytes())
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'de> serde::Deserialize<'de> for Buf8 {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            buf8(deserializer)
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! The reference counts of the heap allocations of a [`SmolBuf`](crate::SmolBuf).
+
+#[cfg(feature = "alloc")]
+use alloc::{rc::Rc, sync::Arc};
+#[cfg(feature = "alloc")]
+use core::mem::ManuallyDrop;
+#[cfg(feature = "alloc")]
+use core::ptr;
+
+/// The kind of reference count of a [`SmolBuf`](crate::SmolBuf) or
+/// [`SmolStr`](crate::SmolStr), which are backed by an `Arc<[u8]>` by default.
+///
+/// This is implemented by [`ArcCounter`] and [`RcCounter`]. The latter is used by
+/// [`LocalSmolBuf`](crate::LocalSmolBuf) and [`LocalSmolStr`](crate::LocalSmolStr).
+pub trait Counter: sealed::Sealed {}
+
+/// Backs heap-allocated values by an `Arc`, which makes them `Send` and `Sync`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ArcCounter;
+
+/// Backs heap-allocated values by an `Rc`, whose `Clone` and `Drop` do not need
+/// atomic operations, but which is neither `Send` nor `Sync`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct RcCounter;
+
+impl Counter for ArcCounter {}
+impl Counter for RcCounter {}
+
+pub(crate) mod sealed {
+    /// The operations on the reference-counted slices of a [`SmolBuf`](crate::SmolBuf),
+    /// which are passed around as raw pointers.
+    pub trait Sealed {
+        /// Allocates a new reference-counted slice holding the concatenated `parts`,
+        /// with a reference count of one.
+        #[cfg(feature = "alloc")]
+        fn alloc(parts: &[&[u8]]) -> *const [u8];
+
+        /// # Safety
+        ///
+        /// `counted` has to be a slice returned by `alloc`, which is still alive.
+        #[cfg(feature = "alloc")]
+        unsafe fn retain(counted: *const [u8]);
+
+        /// Gives up one reference to `counted`, freeing it once the last one is gone.
+        ///
+        /// # Safety
+        ///
+        /// `counted` has to be a slice returned by `alloc`, which is still alive.
+        #[cfg(feature = "alloc")]
+        unsafe fn release(counted: *const [u8]);
+
+        /// # Safety
+        ///
+        /// `counted` has to be a slice returned by `alloc`, which is still alive.
+        #[cfg(feature = "alloc")]
+        unsafe fn strong_count(counted: *const [u8]) -> usize;
+
+        /// Returns `true` if there are no other references to `counted`.
+        ///
+        /// # Safety
+        ///
+        /// `counted` has to be a slice returned by `alloc`, which is still alive.
+        #[cfg(feature = "alloc")]
+        unsafe fn is_unique(counted: *const [u8]) -> bool;
+    }
+}
+
+macro_rules! counter {
+    ($($counter:ty => $rc:ident),*) => {$(
+        impl sealed::Sealed for $counter {
+            #[cfg(feature = "alloc")]
+            fn alloc(parts: &[&[u8]]) -> *const [u8] {
+                let len = parts.iter().map(|part| part.len()).sum();
+                let mut rc = $rc::<[u8]>::new_uninit_slice(len);
+                let data = $rc::get_mut(&mut rc).unwrap().as_mut_ptr() as *mut u8;
+                let mut offset = 0;
+                for part in parts {
+                    unsafe { ptr::copy_nonoverlapping(part.as_ptr(), data.add(offset), part.len()) };
+                    offset += part.len();
+                }
+                $rc::into_raw(unsafe { rc.assume_init() })
+            }
+
+            #[cfg(feature = "alloc")]
+            #[inline]
+            unsafe fn retain(counted: *const [u8]) {
+                $rc::increment_strong_count(counted);
+            }
+
+            #[cfg(feature = "alloc")]
+            #[inline]
+            unsafe fn release(counted: *const [u8]) {
+                $rc::decrement_strong_count(counted);
+            }
+
+            #[cfg(feature = "alloc")]
+            #[inline]
+            unsafe fn strong_count(counted: *const [u8]) -> usize {
+                $rc::strong_count(&ManuallyDrop::new($rc::from_raw(counted)))
+            }
+
+            #[cfg(feature = "alloc")]
+            #[inline]
+            unsafe fn is_unique(counted: *const [u8]) -> bool {
+                $rc::get_mut(&mut ManuallyDrop::new($rc::from_raw(counted))).is_some()
+            }
+        }
+    )*};
+}
+
+counter!(ArcCounter => Arc, RcCounter => Rc);
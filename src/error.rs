@@ -0,0 +1,143 @@
+#[cfg(feature = "alloc")]
+use alloc::alloc::Layout;
+use core::fmt;
+use core::ops::Deref;
+use core::str::Utf8Error;
+
+/// The error returned when a value is too long to be converted into another type.
+///
+/// The original value can be recovered using [`into_inner`](Self::into_inner).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LengthError<T>(pub(crate) T);
+
+impl<T> LengthError<T> {
+    /// Returns the value that failed to convert.
+    #[inline]
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+impl<T> fmt::Display for LengthError<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("value is too long for the target type")
+    }
+}
+
+impl<T: fmt::Debug> core::error::Error for LengthError<T> {}
+
+/// The error returned when converting a buffer into a string fails because it is not valid UTF-8.
+///
+/// The original buffer can be recovered using [`into_bytes`](Self::into_bytes).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromUtf8Error<B> {
+    pub(crate) bytes: B,
+    pub(crate) error: Utf8Error,
+}
+
+impl<B: Deref<Target = [u8]>> FromUtf8Error<B> {
+    /// Returns a slice of the bytes that failed to convert.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<B> FromUtf8Error<B> {
+    /// Returns the buffer that failed to convert.
+    #[inline]
+    pub fn into_bytes(self) -> B {
+        self.bytes
+    }
+
+    /// Returns details about the conversion failure.
+    #[inline]
+    pub fn utf8_error(&self) -> Utf8Error {
+        self.error
+    }
+}
+
+impl<B> fmt::Display for FromUtf8Error<B> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.error, f)
+    }
+}
+
+impl<B: fmt::Debug> core::error::Error for FromUtf8Error<B> {}
+
+/// The error returned when a value does not fit inline,
+/// and can not be allocated on the heap.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TryReserveError {
+    len: usize,
+    kind: TryReserveErrorKind,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum TryReserveErrorKind {
+    #[cfg(not(feature = "alloc"))]
+    Disabled,
+    #[cfg(feature = "alloc")]
+    CapacityOverflow,
+    #[cfg(feature = "alloc")]
+    AllocError,
+}
+
+impl TryReserveError {
+    #[cfg(not(feature = "alloc"))]
+    pub(crate) fn disabled(len: usize) -> Self {
+        let kind = TryReserveErrorKind::Disabled;
+        Self { len, kind }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn capacity_overflow(len: usize) -> Self {
+        let kind = TryReserveErrorKind::CapacityOverflow;
+        Self { len, kind }
+    }
+
+    #[cfg(feature = "alloc")]
+    pub(crate) fn alloc_error(len: usize) -> Self {
+        let kind = TryReserveErrorKind::AllocError;
+        Self { len, kind }
+    }
+
+    /// Panics or aborts in the same way as the infallible constructors.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn handle(self) -> ! {
+        match self.kind {
+            TryReserveErrorKind::CapacityOverflow => panic!("capacity overflow"),
+            TryReserveErrorKind::AllocError => match Layout::array::<u8>(self.len) {
+                Ok(layout) => alloc::alloc::handle_alloc_error(layout),
+                Err(_) => panic!("capacity overflow"),
+            },
+        }
+    }
+
+    /// Returns the length of the value that failed to be constructed.
+    #[inline]
+    pub fn requested_len(&self) -> usize {
+        self.len
+    }
+}
+
+impl fmt::Display for TryReserveError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let len = self.len;
+        match self.kind {
+            #[cfg(not(feature = "alloc"))]
+            TryReserveErrorKind::Disabled => write!(
+                f,
+                "{len} bytes do not fit inline, and heap allocation is disabled"
+            ),
+            #[cfg(feature = "alloc")]
+            TryReserveErrorKind::CapacityOverflow => {
+                write!(f, "capacity overflow while allocating {len} bytes")
+            }
+            #[cfg(feature = "alloc")]
+            TryReserveErrorKind::AllocError => write!(f, "memory allocation of {len} bytes failed"),
+        }
+    }
+}
+
+impl core::error::Error for TryReserveError {}
@@ -0,0 +1,232 @@
+use core::cmp::Ordering;
+use core::hash::{BuildHasherDefault, Hasher};
+use core::ops::Deref;
+use core::sync::atomic;
+use core::{fmt, hash};
+
+use crate::{hasher, header, Buf24, Repr, SmolStr, Str24};
+
+/// A [`Str24`] which caches the hash of heap-allocated strings.
+///
+/// The hash is computed on first use, and stored next to the reference count in the
+/// heap allocation, so it is shared by all clones. Besides [`Hash`], it is also used by
+/// [`PartialEq`] to tell apart strings with different hashes without comparing them.
+///
+/// As the hash does not match the one of `str`, this does not implement `Borrow<str>`,
+/// so a map with `HashedStr24` keys can not be queried with a `&str`. Use such maps
+/// with [`BuildCachedHasher`] to hash the keys in constant time.
+///
+/// Strings created by [`HashedStr24::new_static`] have no heap allocation
+/// to store their hash in, and are hashed on every use.
+#[derive(Clone, Default)]
+pub struct HashedStr24(Str24);
+
+impl HashedStr24 {
+    /// Constructs inline variant of `HashedStr24`.
+    ///
+    /// Panics if `text.len() > 23`.
+    #[inline]
+    pub const fn new_inline(text: &str) -> HashedStr24 {
+        Self(Str24::new_inline(text))
+    }
+
+    /// Constructs a `HashedStr24` from a statically allocated string.
+    ///
+    /// This never allocates.
+    #[inline]
+    pub fn new_static(text: &'static str) -> HashedStr24 {
+        Self(Str24::new_static(text))
+    }
+
+    pub fn new<T>(text: T) -> HashedStr24
+    where
+        T: AsRef<str>,
+    {
+        let text = text.as_ref();
+        if text.len() <= Str24::INLINE_CAP {
+            return Self::new_inline(text);
+        }
+        let data = header::try_alloc_hashed(text.as_bytes()).unwrap_or_else(|err| err.handle());
+        Self(SmolStr(Buf24::from_header(data)))
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        self.0.as_str()
+    }
+
+    /// Returns the underlying [`Str24`] of this string.
+    #[inline(always)]
+    pub fn as_str24(&self) -> &Str24 {
+        &self.0
+    }
+
+    /// Returns the hash of this string, computing and caching it on first use.
+    pub fn cached_hash(&self) -> u64 {
+        let Some(cached) = self.hash_slot() else {
+            return hash_bytes(self.as_bytes());
+        };
+        match cached.load(atomic::Ordering::Relaxed) {
+            0 => {
+                let hash = hash_bytes(self.as_bytes());
+                cached.store(hash, atomic::Ordering::Relaxed);
+                hash
+            }
+            hash => hash,
+        }
+    }
+
+    /// Returns the cached hash, if it was computed already.
+    #[inline]
+    fn known_hash(&self) -> Option<u64> {
+        let hash = self.hash_slot()?.load(atomic::Ordering::Relaxed);
+        (hash != 0).then_some(hash)
+    }
+
+    /// Returns where the hash is cached in the heap allocation of this string.
+    #[inline]
+    fn hash_slot(&self) -> Option<&atomic::AtomicU64> {
+        let data = self.0 .0.header_data()?;
+        unsafe { header::hash(data) }
+    }
+}
+
+impl Deref for HashedStr24 {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq for HashedStr24 {
+    fn eq(&self, other: &HashedStr24) -> bool {
+        if let (Some(hash), Some(other_hash)) = (self.known_hash(), other.known_hash()) {
+            if hash != other_hash {
+                return false;
+            }
+        }
+        self.0 == other.0
+    }
+}
+
+impl Eq for HashedStr24 {}
+
+impl PartialEq<str> for HashedStr24 {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<'a> PartialEq<&'a str> for HashedStr24 {
+    fn eq(&self, other: &&'a str) -> bool {
+        self.as_str() == *other
+    }
+}
+
+impl Ord for HashedStr24 {
+    fn cmp(&self, other: &HashedStr24) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for HashedStr24 {
+    fn partial_cmp(&self, other: &HashedStr24) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl hash::Hash for HashedStr24 {
+    fn hash<H: Hasher>(&self, hasher: &mut H) {
+        hasher.write_u64(self.cached_hash());
+    }
+}
+
+impl fmt::Debug for HashedStr24 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for HashedStr24 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl AsRef<str> for HashedStr24 {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl From<&str> for HashedStr24 {
+    #[inline]
+    fn from(text: &str) -> HashedStr24 {
+        HashedStr24::new(text)
+    }
+}
+
+impl From<Str24> for HashedStr24 {
+    /// Converts a [`Str24`] into a [`HashedStr24`].
+    ///
+    /// Heap-allocated strings are copied into a new allocation with room for the hash,
+    /// unless they were converted from a [`HashedStr24`].
+    #[inline]
+    fn from(text: Str24) -> HashedStr24 {
+        let text = Self(text);
+        if text.0.kind() == Repr::Heap && text.hash_slot().is_none() {
+            return Self::new(text.as_str());
+        }
+        text
+    }
+}
+
+impl From<HashedStr24> for Str24 {
+    #[inline(always)]
+    fn from(text: HashedStr24) -> Str24 {
+        text.0
+    }
+}
+
+/// A [`Hasher`] which uses the cached hash of [`HashedStr24`] keys as is.
+///
+/// Other values are hashed with a fast, but not DoS-resistant hash function.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CachedHasher(u64);
+
+/// A [`BuildHasher`](core::hash::BuildHasher) creating [`CachedHasher`]s.
+pub type BuildCachedHasher = BuildHasherDefault<CachedHasher>;
+
+impl CachedHasher {
+    #[inline(always)]
+    fn add(&mut self, word: u64) {
+        self.0 = hasher::mix(self.0, word);
+    }
+}
+
+impl Hasher for CachedHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        self.0 = hasher::mix_words(self.0, bytes);
+        self.add(bytes.len() as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, n: u64) {
+        self.add(n);
+    }
+}
+
+/// Hashes `bytes` for the cache, using `0` as the marker for a missing hash.
+fn hash_bytes(bytes: &[u8]) -> u64 {
+    let mut hasher = CachedHasher::default();
+    hasher.write(bytes);
+    hasher.finish().max(1)
+}
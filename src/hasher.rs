@@ -0,0 +1,93 @@
+use core::hash::{BuildHasherDefault, Hasher};
+
+/// A [`Hasher`] which consumes short byte strings as a fixed number of zero-padded words,
+/// followed by their length.
+///
+/// The inline contents of the types in this crate are at most 31 bytes long,
+/// so hashing them takes two or four word steps, without a loop over the remaining bytes.
+/// The types hash exactly like the `str` or `[u8]` they borrow as, so a map using this
+/// hasher can still be queried with those. This also means that their `Hash` impls are
+/// not any faster with other hashers, such as the default one of `HashMap`.
+///
+/// This is fast, but not DoS-resistant.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct WordHasher(u64);
+
+/// A [`BuildHasher`](core::hash::BuildHasher) creating [`WordHasher`]s.
+pub type BuildWordHasher = BuildHasherDefault<WordHasher>;
+
+const SEED: u64 = 0x51_7c_c1_b7_27_22_0a_95;
+
+/// Mixes `word` into the hash `state`.
+///
+/// This is the step shared by [`WordHasher`] and the `CachedHasher`.
+#[inline(always)]
+pub(crate) const fn mix(state: u64, word: u64) -> u64 {
+    (state.rotate_left(5) ^ word).wrapping_mul(SEED)
+}
+
+/// Mixes `bytes` into the hash `state` word by word, followed by the zero-padded rest.
+#[inline]
+pub(crate) fn mix_words(mut state: u64, bytes: &[u8]) -> u64 {
+    let mut words = bytes.chunks_exact(8);
+    for word in &mut words {
+        state = mix(state, u64::from_le_bytes(word.try_into().unwrap()));
+    }
+    let mut rest = [0; 8];
+    rest[..words.remainder().len()].copy_from_slice(words.remainder());
+    mix(state, u64::from_le_bytes(rest))
+}
+
+impl WordHasher {
+    #[inline(always)]
+    fn add(&mut self, word: u64) {
+        self.0 = mix(self.0, word);
+    }
+
+    /// Adds the `M` bytes of `padded` as words.
+    #[inline(always)]
+    fn add_words<const M: usize>(&mut self, padded: [u8; M]) {
+        for word in padded.chunks_exact(8) {
+            self.add(u64::from_le_bytes(word.try_into().unwrap()));
+        }
+    }
+}
+
+impl Hasher for WordHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        let len = bytes.len();
+        if len <= 16 {
+            let mut padded = [0; 16];
+            padded[..len].copy_from_slice(bytes);
+            self.add_words(padded);
+        } else if len <= 32 {
+            let mut padded = [0; 32];
+            padded[..len].copy_from_slice(bytes);
+            self.add_words(padded);
+        } else {
+            self.0 = mix_words(self.0, bytes);
+        }
+        self.add(len as u64);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, n: u8) {
+        self.add(n as u64);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, n: u64) {
+        self.add(n);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, n: usize) {
+        self.add(n as u64);
+    }
+}
@@ -0,0 +1,264 @@
+//! Heap allocations with the reference count in a header in front of the bytes.
+//!
+//! Unlike `Arc<[u8]>`, which aborts the process if it can not be allocated,
+//! these are allocated through the fallible allocator API,
+//! using either the global allocator or a [`SmolAlloc`].
+
+use alloc::alloc::{alloc, dealloc, Layout};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::mem;
+use core::ptr::{self, NonNull};
+#[cfg(target_has_atomic = "64")]
+use core::sync::atomic::AtomicU64;
+use core::sync::atomic::{fence, AtomicUsize, Ordering};
+
+use crate::TryReserveError;
+
+/// An allocator for the heap part of values created by
+/// [`Buf24::new_in`](crate::Buf24::new_in) or [`Str24::new_in`](crate::Str24::new_in).
+///
+/// Each of these values keeps its allocator alive, and frees its memory through it
+/// once the last clone is dropped.
+///
+/// # Safety
+///
+/// The memory returned by `allocate` has to be valid for reads and writes of `layout`,
+/// and has to stay valid until it is passed to `deallocate`.
+pub unsafe trait SmolAlloc: Send + Sync {
+    /// Allocates memory for `layout`, returning a null pointer on failure.
+    fn allocate(&self, layout: Layout) -> *mut u8;
+
+    /// Frees memory returned by `allocate`.
+    ///
+    /// # Safety
+    ///
+    /// `ptr` has to be returned by `allocate` of this allocator, called with the same `layout`.
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout);
+}
+
+/// The header directly in front of the bytes.
+///
+/// Allocations which need more fields store them in front of the header, so that values
+/// created with the global allocator, and without a cached hash, only pay for these two.
+#[repr(C)]
+struct Header {
+    count: AtomicUsize,
+    /// The length of the bytes, with the flags for the fields in front of the header
+    /// in its top bits.
+    len: usize,
+}
+
+/// Set for allocations created by [`try_alloc_hashed`], which store the hash of the bytes
+/// as computed by [`HashedStr24`](crate::HashedStr24) in front of the header,
+/// or `0` if it was not computed yet.
+const HASHED: usize = 1 << (usize::BITS - 1);
+/// Set for allocations created with a [`SmolAlloc`], which is stored at the start
+/// of the allocation.
+const CUSTOM_ALLOC: usize = 1 << (usize::BITS - 2);
+const LEN_MASK: usize = !(HASHED | CUSTOM_ALLOC);
+
+/// The offset of the bytes from the start of the header.
+const DATA_OFFSET: usize = mem::size_of::<Header>();
+
+/// The same limit as `Arc` uses to prevent the reference count from overflowing.
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+const _: () = {
+    assert!(mem::align_of::<u64>() <= mem::align_of::<Header>());
+    assert!(mem::align_of::<Arc<dyn SmolAlloc>>() <= mem::align_of::<Header>());
+};
+
+/// Returns the size of the fields in front of the header, for the flags in `len`.
+fn prefix_size(len: usize) -> usize {
+    let mut size = 0;
+    if len & HASHED != 0 {
+        size += mem::size_of::<u64>();
+    }
+    if len & CUSTOM_ALLOC != 0 {
+        size += mem::size_of::<Arc<dyn SmolAlloc>>();
+    }
+    size
+}
+
+fn layout(len: usize) -> Option<Layout> {
+    let size = (prefix_size(len) + DATA_OFFSET).checked_add(len & LEN_MASK)?;
+    Layout::from_size_align(size, mem::align_of::<Header>()).ok()
+}
+
+/// Allocates a copy of `bytes` behind a new header with a reference count of one,
+/// and returns a pointer to the copied bytes.
+///
+/// This uses `alloc`, or the global allocator if it is `None`.
+#[inline]
+pub(crate) fn try_alloc(
+    bytes: &[u8],
+    alloc: Option<Arc<dyn SmolAlloc>>,
+) -> Result<NonNull<u8>, TryReserveError> {
+    try_alloc_with(bytes, alloc, false)
+}
+
+/// Like [`try_alloc`] using the global allocator, but leaves room for a cached hash.
+#[cfg(target_has_atomic = "64")]
+#[inline]
+pub(crate) fn try_alloc_hashed(bytes: &[u8]) -> Result<NonNull<u8>, TryReserveError> {
+    try_alloc_with(bytes, None, true)
+}
+
+fn try_alloc_with(
+    bytes: &[u8],
+    alloc: Option<Arc<dyn SmolAlloc>>,
+    hashed: bool,
+) -> Result<NonNull<u8>, TryReserveError> {
+    let len = bytes.len();
+    let mut flags = 0;
+    if hashed {
+        flags |= HASHED;
+    }
+    if alloc.is_some() {
+        flags |= CUSTOM_ALLOC;
+    }
+    let layout = match len & !LEN_MASK {
+        0 => layout(len | flags),
+        _ => None,
+    }
+    .ok_or(TryReserveError::capacity_overflow(len))?;
+    let start = match &alloc {
+        Some(alloc) => alloc.allocate(layout),
+        None => unsafe { self::alloc(layout) },
+    };
+    if start.is_null() {
+        return Err(TryReserveError::alloc_error(len));
+    }
+
+    unsafe {
+        let mut field = start;
+        if let Some(alloc) = alloc {
+            field.cast::<Arc<dyn SmolAlloc>>().write(alloc);
+            field = field.add(mem::size_of::<Arc<dyn SmolAlloc>>());
+        }
+        if hashed {
+            field.cast::<u64>().write(0);
+            field = field.add(mem::size_of::<u64>());
+        }
+        let count = AtomicUsize::new(1);
+        field.cast::<Header>().write(Header {
+            count,
+            len: len | flags,
+        });
+        let data = field.add(DATA_OFFSET);
+        ptr::copy_nonoverlapping(bytes.as_ptr(), data, len);
+        Ok(NonNull::new_unchecked(data))
+    }
+}
+
+/// Appends `bytes` to `vec`, returning an error instead of aborting if it can not grow.
+pub(crate) fn try_extend(vec: &mut Vec<u8>, bytes: &[u8]) -> Result<(), TryReserveError> {
+    if vec.try_reserve(bytes.len()).is_err() {
+        let len = vec.len().saturating_add(bytes.len());
+        return Err(TryReserveError::alloc_error(len));
+    }
+    vec.extend_from_slice(bytes);
+    Ok(())
+}
+
+/// # Safety
+///
+/// `data` has to be a pointer returned by [`try_alloc`], which is still alive.
+#[inline(always)]
+unsafe fn header<'a>(data: *const u8) -> &'a Header {
+    &*data.sub(DATA_OFFSET).cast::<Header>()
+}
+
+/// Returns the reference count of the allocation of `data`.
+///
+/// # Safety
+///
+/// `data` has to be a pointer returned by [`try_alloc`], which is still alive.
+#[inline]
+pub(crate) unsafe fn strong_count(data: *const u8) -> usize {
+    header(data).count.load(Ordering::Acquire)
+}
+
+/// Prepares the bytes of the allocation of `data` to be mutated,
+/// returning `false` if it is shared.
+///
+/// This forgets the cached hash of an allocation which is not shared, as its bytes may change.
+///
+/// # Safety
+///
+/// `data` has to be a pointer returned by [`try_alloc`], which is still alive.
+#[inline]
+pub(crate) unsafe fn make_mut(data: *const u8) -> bool {
+    if strong_count(data) != 1 {
+        return false;
+    }
+    #[cfg(target_has_atomic = "64")]
+    if let Some(hash) = hash(data) {
+        hash.store(0, Ordering::Relaxed);
+    }
+    true
+}
+
+/// Returns the length of the allocation of `data`, not counting the header.
+///
+/// # Safety
+///
+/// `data` has to be a pointer returned by [`try_alloc`], which is still alive.
+#[inline]
+pub(crate) unsafe fn len(data: *const u8) -> usize {
+    header(data).len & LEN_MASK
+}
+
+/// Returns the cached hash of the allocation of `data`,
+/// or `None` if it was not created by [`try_alloc_hashed`].
+///
+/// # Safety
+///
+/// `data` has to be a pointer returned by [`try_alloc`], which is still alive.
+#[cfg(target_has_atomic = "64")]
+#[inline]
+pub(crate) unsafe fn hash<'a>(data: *const u8) -> Option<&'a AtomicU64> {
+    if header(data).len & HASHED == 0 {
+        return None;
+    }
+    let hash = data.sub(DATA_OFFSET + mem::size_of::<u64>());
+    Some(&*hash.cast::<AtomicU64>())
+}
+
+/// Increments the reference count of the allocation of `data`.
+///
+/// # Safety
+///
+/// `data` has to be a pointer returned by [`try_alloc`], which is still alive.
+#[inline]
+pub(crate) unsafe fn retain(data: *const u8) {
+    let old = header(data).count.fetch_add(1, Ordering::Relaxed);
+    assert!(old <= MAX_REFCOUNT, "reference count overflow");
+}
+
+/// Decrements the reference count of the allocation of `data`,
+/// and frees it once the last reference is gone.
+///
+/// # Safety
+///
+/// `data` has to be a pointer returned by [`try_alloc`], which is still alive.
+/// The caller gives up its reference, and must not use `data` afterwards.
+#[inline]
+pub(crate) unsafe fn release(data: *const u8) {
+    let header = header(data);
+    if header.count.fetch_sub(1, Ordering::Release) != 1 {
+        return;
+    }
+    fence(Ordering::Acquire);
+
+    let len = header.len;
+    let layout = layout(len).unwrap_unchecked();
+    let start = data.sub(DATA_OFFSET + prefix_size(len)) as *mut u8;
+    if len & CUSTOM_ALLOC != 0 {
+        let alloc = ptr::read(start.cast::<Arc<dyn SmolAlloc>>());
+        alloc.deallocate(start, layout);
+    } else {
+        dealloc(start, layout);
+    }
+}
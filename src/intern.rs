@@ -3,55 +3,23 @@ use std::sync::Mutex;
 
 use alloc::sync::Arc;
 
-use crate::{buf16, buf24, Str16, Str24};
+use crate::{SmolString, Str16, Str24};
 
-/// [`Intern16`] is an interner storing and yielding [`Str16`] string types.
+/// [`Intern`] is an interner storing and yielding strings of type `S`,
+/// which can be any of the [`SmolString`] types.
 ///
 /// The [`intern`](Self::intern) method can be used to intern a string.
 #[derive(Clone, Default)]
-pub struct Intern16 {
-    set: Arc<Mutex<HashSet<Str16>>>,
-}
-
-impl Intern16 {
-    /// Construct a new empty interner.
-    pub fn new() -> Self {
-        Self::default()
-    }
-
-    /// Intern a given string.
-    ///
-    /// This will return the canonical inline representation for small strings,
-    /// and will otherwise return an interned [`Str16`] shared with the interner.
-    pub fn intern(&self, text: impl AsRef<str>) -> Str16 {
-        self.intern_str(text.as_ref())
-    }
-
-    fn intern_str(&self, text: &str) -> Str16 {
-        if text.len() <= buf16::INLINE_CAP {
-            return Str16::from(text);
-        }
-
-        let mut set = self.set.lock().unwrap();
-        if let Some(str) = set.get(text) {
-            return str.clone();
-        }
-
-        let str = Str16::from(text);
-        set.insert(str.clone());
-        str
-    }
+pub struct Intern<S> {
+    set: Arc<Mutex<HashSet<S>>>,
 }
 
+/// [`Intern16`] is an interner storing and yielding [`Str16`] string types.
+pub type Intern16 = Intern<Str16>;
 /// [`Intern24`] is an interner storing and yielding [`Str24`] string types.
-///
-/// The [`intern`](Self::intern) method can be used to intern a string.
-#[derive(Clone, Default)]
-pub struct Intern24 {
-    set: Arc<Mutex<HashSet<Str24>>>,
-}
+pub type Intern24 = Intern<Str24>;
 
-impl Intern24 {
+impl<S: SmolString> Intern<S> {
     /// Construct a new empty interner.
     pub fn new() -> Self {
         Self::default()
@@ -60,24 +28,22 @@ impl Intern24 {
     /// Intern a given string.
     ///
     /// This will return the canonical inline representation for small strings,
-    /// and will otherwise return an interned [`Str24`] shared with the interner.
-    pub fn intern(&self, text: impl AsRef<str>) -> Str24 {
+    /// and will otherwise return an interned string shared with the interner.
+    pub fn intern(&self, text: impl AsRef<str>) -> S {
         self.intern_str(text.as_ref())
     }
 
-    fn intern_str(&self, text: &str) -> Str24 {
-        if text.len() <= buf24::INLINE_CAP {
-            return Str24::from(text);
-        }
-
-        let mut set = self.set.lock().unwrap();
-        if let Some(str) = set.get(text) {
-            return str.clone();
-        }
-
-        let str = Str24::from(text);
-        set.insert(str.clone());
-        str
+    fn intern_str(&self, text: &str) -> S {
+        S::intern_with(text, |text| {
+            let mut set = self.set.lock().unwrap();
+            if let Some(str) = set.get(text) {
+                return str.clone();
+            }
+
+            let str = S::new(text);
+            set.insert(str.clone());
+            str
+        })
     }
 }
 
@@ -98,6 +64,8 @@ mod tests {
         let heap2 = interner.intern("some text that is not so smol anymore");
 
         assert!(ptr::eq(heap1.as_str(), heap2.as_str()));
+        assert!(heap1.ptr_eq(&heap2));
+        assert_eq!(heap1.strong_count(), Some(3));
     }
 
     #[test]
@@ -111,5 +79,18 @@ mod tests {
         let heap2 = interner.intern("some text that is not so smol anymore");
 
         assert!(ptr::eq(heap1.as_str(), heap2.as_str()));
+        assert!(heap1.ptr_eq(&heap2));
+        assert_eq!(heap1.strong_count(), Some(3));
+    }
+
+    #[test]
+    fn test_intern_local() {
+        let interner = Intern::<crate::LocalStr24>::new();
+
+        let heap1 = interner.intern("some text that is not so smol anymore");
+        let heap2 = interner.intern("some text that is not so smol anymore");
+
+        assert!(heap1.ptr_eq(&heap2));
+        assert_eq!(heap1.strong_count(), Some(3));
     }
 }
@@ -3,19 +3,44 @@
 #![cfg_attr(feature = "document-features", doc = document_features::document_features!())]
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![no_std]
+#[cfg(feature = "alloc")]
 extern crate alloc;
 #[cfg(feature = "std")]
 extern crate std;
 
-mod buf16;
-mod buf24;
-mod str16;
-mod str24;
+mod buf8;
+mod counter;
+mod error;
+#[cfg(all(feature = "alloc", target_has_atomic = "64"))]
+mod hashed;
+mod hasher;
+#[cfg(feature = "alloc")]
+mod header;
+#[cfg(feature = "alloc")]
+mod owner;
+mod packed_str24;
+mod prefix_str16;
+mod repr;
+mod smol_buf;
+mod smol_str;
+mod str8;
+mod traits;
 
-pub use buf16::*;
-pub use buf24::*;
-pub use str16::*;
-pub use str24::*;
+pub use buf8::*;
+pub use counter::{ArcCounter, Counter, RcCounter};
+pub use error::*;
+#[cfg(all(feature = "alloc", target_has_atomic = "64"))]
+pub use hashed::*;
+pub use hasher::*;
+#[cfg(feature = "alloc")]
+pub use header::SmolAlloc;
+pub use packed_str24::*;
+pub use prefix_str16::*;
+pub use repr::*;
+pub use smol_buf::*;
+pub use smol_str::*;
+pub use str8::*;
+pub use traits::*;
 
 #[cfg(feature = "intern")]
 mod intern;
@@ -0,0 +1,108 @@
+//! External owners of bytes, kept alive behind a reference count.
+//!
+//! Like `bytes::Bytes`, values created by [`SmolBuf::from_owner`](crate::SmolBuf::from_owner)
+//! point to a block holding the reference count, the bytes of the owner,
+//! and a vtable which knows how to drop the owner.
+
+use alloc::boxed::Box;
+use core::ptr::{self, NonNull};
+use core::slice;
+use core::sync::atomic::{fence, AtomicUsize, Ordering};
+
+/// The part of an owner block which does not depend on the type of the owner.
+#[repr(C)]
+pub(crate) struct Owner {
+    count: AtomicUsize,
+    data: *const u8,
+    len: usize,
+    vtable: &'static Vtable,
+}
+
+struct Vtable {
+    /// Frees the block, including the owner.
+    drop: unsafe fn(NonNull<Owner>),
+}
+
+#[repr(C)]
+struct Block<T> {
+    owner: Owner,
+    value: T,
+}
+
+impl<T> Block<T> {
+    const VTABLE: Vtable = Vtable { drop: Self::drop };
+
+    unsafe fn drop(owner: NonNull<Owner>) {
+        drop(Box::from_raw(owner.cast::<Self>().as_ptr()));
+    }
+}
+
+/// The same limit as `Arc` uses to prevent the reference count from overflowing.
+const MAX_REFCOUNT: usize = isize::MAX as usize;
+
+/// Moves `value` into a new block with a reference count of one.
+pub(crate) fn new<T: AsRef<[u8]> + Send + Sync + 'static>(value: T) -> NonNull<Owner> {
+    let owner = Owner {
+        count: AtomicUsize::new(1),
+        data: ptr::null(),
+        len: 0,
+        vtable: &Block::<T>::VTABLE,
+    };
+    let block = Box::into_raw(Box::new(Block { owner, value }));
+    unsafe {
+        // the block does not move anymore, so the bytes stay put even if they live inside `value`
+        let bytes = (*block).value.as_ref();
+        (*block).owner.data = bytes.as_ptr();
+        (*block).owner.len = bytes.len();
+        NonNull::new_unchecked(block.cast::<Owner>())
+    }
+}
+
+/// Returns all the bytes of the owner.
+///
+/// # Safety
+///
+/// `owner` has to be a pointer returned by [`new`], which is still alive.
+#[inline]
+pub(crate) unsafe fn bytes<'a>(owner: *const Owner) -> &'a [u8] {
+    let owner = &*owner;
+    slice::from_raw_parts(owner.data, owner.len)
+}
+
+/// Returns the reference count of `owner`.
+///
+/// # Safety
+///
+/// `owner` has to be a pointer returned by [`new`], which is still alive.
+#[inline]
+pub(crate) unsafe fn strong_count(owner: *const Owner) -> usize {
+    (*owner).count.load(Ordering::Acquire)
+}
+
+/// Increments the reference count of `owner`.
+///
+/// # Safety
+///
+/// `owner` has to be a pointer returned by [`new`], which is still alive.
+#[inline]
+pub(crate) unsafe fn retain(owner: *const Owner) {
+    let old = (*owner).count.fetch_add(1, Ordering::Relaxed);
+    assert!(old <= MAX_REFCOUNT, "reference count overflow");
+}
+
+/// Decrements the reference count of `owner`, and drops it once the last reference is gone.
+///
+/// # Safety
+///
+/// `owner` has to be a pointer returned by [`new`], which is still alive.
+/// The caller gives up its reference, and must not use `owner` afterwards.
+#[inline]
+pub(crate) unsafe fn release(owner: *const Owner) {
+    if (*owner).count.fetch_sub(1, Ordering::Release) != 1 {
+        return;
+    }
+    fence(Ordering::Acquire);
+
+    let drop = (*owner).vtable.drop;
+    drop(NonNull::new_unchecked(owner as *mut Owner));
+}
@@ -0,0 +1,361 @@
+#[cfg(feature = "alloc")]
+use alloc::string::String;
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::convert::Infallible;
+use core::str::from_utf8_unchecked;
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
+use core::{fmt, hash};
+
+use crate::{Buf24, Repr, SmolStr, Str24, TryReserveError};
+
+/// A [`Str24`] which stores ASCII identifiers of up to 30 characters inline.
+///
+/// * `size_of::<PackedStr24>() == 24`
+/// * `size_of::<Option<PackedStr24>>() == size_of::<PackedStr24>()`
+/// * `Clone` is `O(1)`
+/// * Strings of up to 23 bytes are stored inline, like in a [`Str24`]
+/// * Strings of up to 30 characters from `[a-zA-Z0-9_.-]` are packed inline,
+///   at slightly more than 6 bits per character
+/// * All other strings are stored like in a [`Str24`]
+///
+/// As packed strings have to be decoded first, this does not implement
+/// `Deref<Target = str>`. Use [`to_str`](Self::to_str) to decode a string into a buffer
+/// on the stack instead, or convert it into a [`Str24`].
+#[derive(Clone, Default)]
+pub struct PackedStr24(Str24);
+
+/// The characters which can be packed, in the order of their codes.
+const ALPHABET: &[u8; 65] = b"-.0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZ_abcdefghijklmnopqrstuvwxyz";
+const RADIX: u64 = ALPHABET.len() as u64;
+/// Marks the bytes which are not in the [`ALPHABET`].
+const INVALID: u8 = u8::MAX;
+/// The code of each byte, or `INVALID`.
+static CODES: [u8; 256] = codes();
+
+const fn codes() -> [u8; 256] {
+    let mut codes = [INVALID; 256];
+    let mut i = 0;
+    while i < ALPHABET.len() {
+        codes[ALPHABET[i] as usize] = i as u8;
+        i += 1;
+    }
+    codes
+}
+
+// The characters are split into groups, whose codes are stored as numbers in base `RADIX`.
+// The first two groups have 10 characters each, which fit into the low 61 bits of the first
+// two words. The third group has 9 characters, which fit into 55 of the 56 bits of the last
+// word left next to the tag. The remaining 7 bits hold the last character.
+const GROUP_BITS: u32 = 61;
+const LAST_GROUP_BITS: u32 = 55;
+
+impl PackedStr24 {
+    /// The number of characters that can be packed inline.
+    pub const PACKED_CAP: usize = 30;
+
+    /// Constructs a `PackedStr24` from a statically allocated string.
+    ///
+    /// This never allocates, and the string is not packed.
+    #[inline]
+    pub const fn new_static(text: &'static str) -> Self {
+        Self(Str24::new_static(text))
+    }
+
+    /// Constructs a `PackedStr24`, packing `text` if it is an identifier too long to be
+    /// stored inline otherwise.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn new<T>(text: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let text = text.as_ref();
+        Self::pack(text).unwrap_or_else(|| Self(Str24::new(text)))
+    }
+
+    /// Constructs a `PackedStr24`, returning an error if `text` can neither be stored
+    /// inline nor packed, and can not be allocated on the heap.
+    ///
+    /// See [`Str24::try_new`].
+    #[inline]
+    pub fn try_new<T>(text: T) -> Result<Self, TryReserveError>
+    where
+        T: AsRef<str>,
+    {
+        let text = text.as_ref();
+        match Self::pack(text) {
+            Some(packed) => Ok(packed),
+            None => Str24::try_new(text).map(Self),
+        }
+    }
+
+    /// Packs `text`, if it does not fit inline, and consists of up to
+    /// [`PACKED_CAP`](Self::PACKED_CAP) characters of the alphabet.
+    fn pack(text: &str) -> Option<Self> {
+        let len = text.len();
+        if len <= Str24::INLINE_CAP || len > Self::PACKED_CAP {
+            return None;
+        }
+        let mut codes = [0; Self::PACKED_CAP];
+        for (code, &byte) in codes.iter_mut().zip(text.as_bytes()) {
+            *code = match CODES[byte as usize] {
+                INVALID => return None,
+                valid => valid as u64,
+            };
+        }
+
+        let group = |codes: &[u64]| codes.iter().fold(0, |acc, code| acc * RADIX + code);
+        let last = codes[29];
+        let words = [
+            group(&codes[..10]) | (last & 0b111) << GROUP_BITS,
+            group(&codes[10..20]) | (last >> 3 & 0b111) << GROUP_BITS,
+            group(&codes[20..29]) | (last >> 6) << LAST_GROUP_BITS,
+        ];
+        Some(Self(SmolStr(Buf24::from_packed(words, len))))
+    }
+
+    /// Returns the string, decoding it into `buf` if it is packed.
+    #[inline]
+    pub fn to_str<'a>(&'a self, buf: &'a mut [u8; Self::PACKED_CAP]) -> &'a str {
+        let Some((words, len)) = self.0 .0.packed() else {
+            return self.0.as_str();
+        };
+
+        let ungroup = |mut group: u64, chars: &mut [u8]| {
+            for char in chars.iter_mut().rev() {
+                *char = ALPHABET[(group % RADIX) as usize];
+                group /= RADIX;
+            }
+        };
+        ungroup(words[0] & ((1 << GROUP_BITS) - 1), &mut buf[..10]);
+        ungroup(words[1] & ((1 << GROUP_BITS) - 1), &mut buf[10..20]);
+        ungroup(words[2] & ((1 << LAST_GROUP_BITS) - 1), &mut buf[20..29]);
+        let last = words[0] >> GROUP_BITS
+            | (words[1] >> GROUP_BITS) << 3
+            | (words[2] >> LAST_GROUP_BITS) << 6;
+        buf[29] = ALPHABET[last as usize];
+
+        // the alphabet is ASCII
+        unsafe { from_utf8_unchecked(&buf[..len]) }
+    }
+
+    /// Returns the string if it is not packed, and can be borrowed as is.
+    #[inline]
+    pub fn as_unpacked(&self) -> Option<&str> {
+        (!self.is_packed()).then(|| self.0.as_str())
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    #[inline]
+    pub fn to_string(&self) -> String {
+        use alloc::borrow::ToOwned;
+
+        self.to_str(&mut [0; Self::PACKED_CAP]).to_owned()
+    }
+
+    /// Returns `true` if the string is packed.
+    #[inline(always)]
+    pub fn is_packed(&self) -> bool {
+        self.0 .0.packed().is_some()
+    }
+
+    #[inline]
+    pub fn len(&self) -> usize {
+        match self.0 .0.packed() {
+            Some((_, len)) => len,
+            None => self.0.len(),
+        }
+    }
+
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    #[inline(always)]
+    pub fn is_heap_allocated(&self) -> bool {
+        !self.is_packed() && self.0.is_heap_allocated()
+    }
+
+    /// Returns how the contents of this string are stored.
+    ///
+    /// Packed strings are stored [`Repr::Inline`].
+    #[inline]
+    pub fn kind(&self) -> Repr {
+        match self.is_packed() {
+            true => Repr::Inline,
+            false => self.0.kind(),
+        }
+    }
+}
+
+impl PartialEq for PackedStr24 {
+    fn eq(&self, other: &Self) -> bool {
+        if let (Some(packed), Some(other_packed)) = (self.0 .0.packed(), other.0 .0.packed()) {
+            return packed == other_packed;
+        }
+        if self.is_packed() || other.is_packed() {
+            // strings which can be packed are only stored otherwise by `new_static`
+            return self.to_str(&mut [0; Self::PACKED_CAP])
+                == other.to_str(&mut [0; Self::PACKED_CAP]);
+        }
+        self.0 == other.0
+    }
+}
+
+impl Eq for PackedStr24 {}
+
+impl PartialEq<str> for PackedStr24 {
+    fn eq(&self, other: &str) -> bool {
+        self.to_str(&mut [0; Self::PACKED_CAP]) == other
+    }
+}
+
+impl PartialEq<PackedStr24> for str {
+    fn eq(&self, other: &PackedStr24) -> bool {
+        other == self
+    }
+}
+
+impl<'a> PartialEq<&'a str> for PackedStr24 {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<PackedStr24> for &str {
+    fn eq(&self, other: &PackedStr24) -> bool {
+        *self == other
+    }
+}
+
+impl Ord for PackedStr24 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        if !self.is_packed() && !other.is_packed() {
+            return self.0.cmp(&other.0);
+        }
+        let (mut buf, mut other_buf) = ([0; Self::PACKED_CAP], [0; Self::PACKED_CAP]);
+        self.to_str(&mut buf).cmp(other.to_str(&mut other_buf))
+    }
+}
+
+impl PartialOrd for PackedStr24 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl hash::Hash for PackedStr24 {
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        self.to_str(&mut [0; Self::PACKED_CAP]).hash(hasher);
+    }
+}
+
+impl fmt::Debug for PackedStr24 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.to_str(&mut [0; Self::PACKED_CAP]), f)
+    }
+}
+
+impl fmt::Display for PackedStr24 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.to_str(&mut [0; Self::PACKED_CAP]), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&str> for PackedStr24 {
+    #[inline]
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for PackedStr24 {
+    #[inline]
+    fn from(s: String) -> Self {
+        Self::pack(&s).unwrap_or_else(|| Self(Str24::from(s)))
+    }
+}
+
+impl From<Str24> for PackedStr24 {
+    /// Converts a [`Str24`] into a [`PackedStr24`], packing it if possible.
+    ///
+    /// Static strings are kept as they are.
+    #[inline]
+    fn from(text: Str24) -> Self {
+        if text.as_static().is_some() {
+            return Self(text);
+        }
+        Self::pack(&text).unwrap_or(Self(text))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<PackedStr24> for Str24 {
+    /// Converts a [`PackedStr24`] into a [`Str24`], which has to allocate packed strings.
+    #[inline]
+    fn from(text: PackedStr24) -> Self {
+        match text.is_packed() {
+            true => Str24::new(text.to_str(&mut [0; PackedStr24::PACKED_CAP])),
+            false => text.0,
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<PackedStr24> for String {
+    #[inline(always)]
+    fn from(text: PackedStr24) -> Self {
+        text.to_string()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for PackedStr24 {
+    type Err = Infallible;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PackedStr24 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> Result<Self, arbitrary::Error> {
+        let s = <&str>::arbitrary(u)?;
+        Self::try_new(s).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    use crate::PackedStr24;
+    #[cfg(feature = "alloc")]
+    use crate::Str24;
+
+    impl serde::Serialize for PackedStr24 {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            self.to_str(&mut [0; Self::PACKED_CAP])
+                .serialize(serializer)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'de> serde::Deserialize<'de> for PackedStr24 {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            Str24::deserialize(deserializer).map(Self::from)
+        }
+    }
+}
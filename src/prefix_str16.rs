@@ -0,0 +1,642 @@
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box, string::String, sync::Arc};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::convert::Infallible;
+#[cfg(feature = "alloc")]
+use core::mem;
+use core::mem::MaybeUninit;
+use core::ops::Deref;
+#[cfg(feature = "alloc")]
+use core::ptr::NonNull;
+use core::str::from_utf8_unchecked;
+#[cfg(feature = "alloc")]
+use core::str::FromStr;
+use core::{fmt, hash, slice};
+
+#[cfg(feature = "alloc")]
+use crate::header;
+use crate::{Repr, Str16, TryReserveError};
+#[cfg(feature = "alloc")]
+use crate::{SmolBuf, SmolStr};
+
+/// A `PrefixStr16` is a string type with the layout of the Umbra or Arrow "German string":
+///
+/// * `size_of::<PrefixStr16>() == 16`
+/// * `Clone` is `O(1)`
+/// * Strings are stack-allocated if they are up to 12 bytes long
+/// * Longer strings are heap-allocated, but keep a copy of their first 4 bytes inline,
+///   next to a 32 bit length
+/// * Additionally, a `PrefixStr16` can be explicitly created from a `&'static str`
+///   without allocation
+///
+/// Comparing two strings first compares their inline prefixes, and only follows the
+/// pointers if those are equal. This makes sorting many heap-allocated strings faster than
+/// with a [`Str16`], which has 3 more bytes of inline storage instead.
+///
+/// Unlike [`Str16`], the length limits strings to `i32::MAX` bytes,
+/// and `Option<PrefixStr16>` is larger than the string itself.
+/// The type can not be mutated, convert it into a [`Str16`] for that.
+/// It hashes like a `str` as well, see [`SmolStr`](crate::SmolStr) for when hashing is faster.
+#[repr(C, align(8))]
+pub struct PrefixStr16 {
+    /// The length, with `STATIC` set for strings borrowed from `'static` memory.
+    len: u32,
+    /// The first bytes of the string, padded with zeros.
+    prefix: [u8; PREFIX_LEN],
+    /// The pointer to the bytes of a non-inline string.
+    ///
+    /// Inline strings overwrite the pointer with the bytes following the prefix.
+    ptr: *const u8,
+}
+
+const PREFIX_LEN: usize = 4;
+/// Set in the length of strings created by [`PrefixStr16::new_static`].
+const STATIC: u32 = 1 << 31;
+const MAX_LEN: usize = STATIC as usize - 1;
+
+// The pointer is either `'static`, or refers to a thread-safe, reference-counted allocation.
+unsafe impl Send for PrefixStr16 {}
+unsafe impl Sync for PrefixStr16 {}
+
+impl PrefixStr16 {
+    /// The number of bytes that are stored inline.
+    pub const INLINE_CAP: usize = 12;
+
+    /// Constructs inline variant of `PrefixStr16`.
+    ///
+    /// Panics if `text.len()` is larger than [`INLINE_CAP`](Self::INLINE_CAP).
+    #[inline]
+    pub const fn new_inline(text: &str) -> Self {
+        let len = text.len();
+        assert!(len <= Self::INLINE_CAP); // avoids checks in loop
+
+        let mut raw = MaybeUninit::<Self>::zeroed();
+        let buf = raw.as_mut_ptr() as *mut u8;
+
+        let mut i = 0;
+        while i < len {
+            unsafe { buf.add(PREFIX_LEN + i).write(text.as_bytes()[i]) };
+            i += 1
+        }
+
+        unsafe {
+            (buf as *mut u32).write(len as u32);
+            raw.assume_init()
+        }
+    }
+
+    /// Constructs a `PrefixStr16` from a statically allocated string.
+    ///
+    /// This never allocates, and can be used in a `const` context.
+    ///
+    /// Panics if `text.len()` is larger than `i32::MAX`.
+    #[inline]
+    pub const fn new_static(text: &'static str) -> Self {
+        let len = text.len();
+        if len <= Self::INLINE_CAP {
+            return Self::new_inline(text);
+        }
+        assert!(len <= MAX_LEN, "capacity overflow");
+        Self::from_ptr(text.as_bytes(), len as u32 | STATIC)
+    }
+
+    /// Panics if `text.len()` is larger than `i32::MAX`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn new<T>(text: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self::try_new(text).unwrap_or_else(|err| err.handle())
+    }
+
+    /// Constructs a `PrefixStr16`, returning an error if `text` does not fit inline,
+    /// and can not be allocated on the heap.
+    ///
+    /// Without the `alloc` feature, this fails for every `text` longer than
+    /// [`INLINE_CAP`](Self::INLINE_CAP).
+    #[inline]
+    pub fn try_new<T>(text: T) -> Result<Self, TryReserveError>
+    where
+        T: AsRef<str>,
+    {
+        let text = text.as_ref();
+        if text.len() <= Self::INLINE_CAP {
+            return Ok(Self::new_inline(text));
+        }
+        #[cfg(feature = "alloc")]
+        {
+            if text.len() > MAX_LEN {
+                return Err(TryReserveError::capacity_overflow(text.len()));
+            }
+            header::try_alloc(text.as_bytes(), None).map(Self::from_header)
+        }
+        #[cfg(not(feature = "alloc"))]
+        Err(TryReserveError::disabled(text.len()))
+    }
+
+    /// Constructs a non-inline string pointing to `bytes`, which has to outlive it.
+    #[inline]
+    const fn from_ptr(bytes: &[u8], len: u32) -> Self {
+        let mut prefix = [0; PREFIX_LEN];
+        let mut i = 0;
+        while i < PREFIX_LEN && i < bytes.len() {
+            prefix[i] = bytes[i];
+            i += 1;
+        }
+        Self {
+            len,
+            prefix,
+            ptr: bytes.as_ptr(),
+        }
+    }
+
+    /// Takes over the reference to `data`, which was created by [`header::try_alloc`],
+    /// and is at most `MAX_LEN` bytes long.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn from_header(data: NonNull<u8>) -> Self {
+        let bytes = unsafe { slice::from_raw_parts(data.as_ptr(), header::len(data.as_ptr())) };
+        Self::from_ptr(bytes, bytes.len() as u32)
+    }
+
+    /// Returns the pointer to the bytes of an allocation created by [`header::try_alloc`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn header_data(&self) -> Option<*const u8> {
+        self.is_heap_allocated().then_some(self.ptr)
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        let len = self.len();
+        let ptr = match len <= Self::INLINE_CAP {
+            true => unsafe { (self as *const Self as *const u8).add(PREFIX_LEN) },
+            false => self.ptr,
+        };
+        unsafe { from_utf8_unchecked(slice::from_raw_parts(ptr, len)) }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    #[inline(always)]
+    pub fn to_string(&self) -> String {
+        use alloc::borrow::ToOwned;
+
+        self.as_str().to_owned()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        (self.len & !STATIC) as usize
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline(always)]
+    pub fn is_heap_allocated(&self) -> bool {
+        self.len as usize > Self::INLINE_CAP && self.len & STATIC == 0
+    }
+
+    /// Returns how the contents of this string are stored.
+    #[inline]
+    pub fn kind(&self) -> Repr {
+        if self.len() <= Self::INLINE_CAP {
+            Repr::Inline
+        } else if self.len & STATIC != 0 {
+            Repr::Static
+        } else {
+            Repr::Heap
+        }
+    }
+
+    /// Returns the number of values sharing the heap allocation of this string,
+    /// or `None` if it is not heap-allocated.
+    #[inline]
+    pub fn strong_count(&self) -> Option<usize> {
+        #[cfg(feature = "alloc")]
+        return self
+            .header_data()
+            .map(|data| unsafe { header::strong_count(data) });
+        #[cfg(not(feature = "alloc"))]
+        None
+    }
+
+    /// Returns `true` if both strings refer to the same non-inline memory.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.kind() != Repr::Inline && self.ptr == other.ptr
+    }
+
+    /// Returns the underlying `&'static str`, if this string was created from one.
+    ///
+    /// This returns `None` for static strings that were short enough to be stored inline.
+    #[inline]
+    pub fn as_static(&self) -> Option<&'static str> {
+        if self.kind() != Repr::Static {
+            return None;
+        }
+        Some(unsafe { from_utf8_unchecked(slice::from_raw_parts(self.ptr, self.len())) })
+    }
+
+    /// Returns the prefix as a big-endian number, which orders like the prefix itself.
+    #[inline(always)]
+    fn prefix_key(&self) -> u32 {
+        u32::from_be_bytes(self.prefix)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl Drop for PrefixStr16 {
+    fn drop(&mut self) {
+        if let Some(data) = self.header_data() {
+            unsafe { header::release(data) };
+        }
+    }
+}
+
+impl Clone for PrefixStr16 {
+    fn clone(&self) -> Self {
+        #[cfg(feature = "alloc")]
+        if let Some(data) = self.header_data() {
+            unsafe { header::retain(data) };
+        }
+
+        Self {
+            len: self.len,
+            prefix: self.prefix,
+            ptr: self.ptr,
+        }
+    }
+}
+
+impl PartialEq for PrefixStr16 {
+    fn eq(&self, other: &Self) -> bool {
+        if self.len() != other.len() || self.prefix != other.prefix {
+            return false;
+        }
+        // inline strings are padded with zeros, so equal bytes are equal pointers
+        self.ptr == other.ptr || self.as_str() == other.as_str()
+    }
+}
+
+impl Eq for PrefixStr16 {}
+
+impl Default for PrefixStr16 {
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new_inline("")
+    }
+}
+
+impl Deref for PrefixStr16 {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for PrefixStr16 {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<PrefixStr16> for str {
+    fn eq(&self, other: &PrefixStr16) -> bool {
+        other == self
+    }
+}
+
+impl<'a> PartialEq<&'a str> for PrefixStr16 {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<PrefixStr16> for &str {
+    fn eq(&self, other: &PrefixStr16) -> bool {
+        *self == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<String> for PrefixStr16 {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<PrefixStr16> for String {
+    fn eq(&self, other: &PrefixStr16) -> bool {
+        other == self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> PartialEq<&'a String> for PrefixStr16 {
+    fn eq(&self, other: &&'a String) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<PrefixStr16> for &String {
+    fn eq(&self, other: &PrefixStr16) -> bool {
+        *self == other
+    }
+}
+
+impl Ord for PrefixStr16 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // a shorter prefix is padded with zeros, which sort before any other byte
+        match self.prefix_key().cmp(&other.prefix_key()) {
+            Ordering::Equal => self.as_str().cmp(other.as_str()),
+            ordering => ordering,
+        }
+    }
+}
+
+impl PartialOrd for PrefixStr16 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl hash::Hash for PrefixStr16 {
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        self.as_str().hash(hasher);
+    }
+}
+
+impl fmt::Debug for PrefixStr16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for PrefixStr16 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+impl AsRef<str> for PrefixStr16 {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Borrow<str> for PrefixStr16 {
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&str> for PrefixStr16 {
+    #[inline]
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&mut str> for PrefixStr16 {
+    #[inline]
+    fn from(s: &mut str) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&String> for PrefixStr16 {
+    #[inline]
+    fn from(s: &String) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for PrefixStr16 {
+    #[inline(always)]
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Box<str>> for PrefixStr16 {
+    #[inline]
+    fn from(s: Box<str>) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Arc<str>> for PrefixStr16 {
+    #[inline]
+    fn from(s: Arc<str>) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<Cow<'a, str>> for PrefixStr16 {
+    #[inline]
+    fn from(s: Cow<'a, str>) -> Self {
+        Self::new(s)
+    }
+}
+
+impl From<PrefixStr16> for Str16 {
+    /// Converts a [`PrefixStr16`] into a [`Str16`], moving over its heap allocation.
+    ///
+    /// Strings which fit inline into a [`Str16`] are copied.
+    #[inline]
+    fn from(text: PrefixStr16) -> Self {
+        if let Some(text) = text.as_static() {
+            return Self::new_static(text);
+        }
+        if text.len() <= Str16::INLINE_CAP {
+            return Self::new_inline(&text);
+        }
+        #[cfg(feature = "alloc")]
+        if let Some(data) = text.header_data() {
+            mem::forget(text);
+            let data = unsafe { NonNull::new_unchecked(data as *mut u8) };
+            return SmolStr(SmolBuf::from_header(data));
+        }
+        Self::new_inline(&text)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Str16> for PrefixStr16 {
+    /// Converts a [`Str16`] into a [`PrefixStr16`].
+    ///
+    /// Strings created by [`Str16::try_new`] have their allocation moved over,
+    /// all other strings which do not fit inline are copied.
+    #[inline]
+    fn from(text: Str16) -> Self {
+        if let Some(text) = text.as_static() {
+            return Self::new_static(text);
+        }
+        if text.len() <= Self::INLINE_CAP || text.len() > MAX_LEN {
+            return Self::new(&text);
+        }
+        match text.0.into_header() {
+            Ok(data) => Self::from_header(data),
+            Err(buf) => Self::new(unsafe { from_utf8_unchecked(&buf) }),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<PrefixStr16> for Arc<str> {
+    #[inline(always)]
+    fn from(text: PrefixStr16) -> Self {
+        Arc::from(text.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<PrefixStr16> for String {
+    #[inline(always)]
+    fn from(text: PrefixStr16) -> Self {
+        text.as_str().into()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for PrefixStr16 {
+    type Err = Infallible;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for PrefixStr16 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> Result<Self, arbitrary::Error> {
+        let s = <&str>::arbitrary(u)?;
+        Self::try_new(s).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    #[cfg(feature = "alloc")]
+    use alloc::{string::String, vec::Vec};
+    #[cfg(feature = "alloc")]
+    use core::fmt;
+
+    #[cfg(feature = "alloc")]
+    use serde::de::{Deserializer, Error, Unexpected, Visitor};
+
+    use crate::PrefixStr16;
+
+    #[cfg(feature = "alloc")]
+    // https://github.com/serde-rs/serde/blob/629802f2abfd1a54a6072992888fea7ca5bc209f/serde/src/private/de.rs#L56-L125
+    fn prefix_str16<'de: 'a, 'a, D>(deserializer: D) -> Result<PrefixStr16, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct PrefixStr16Visitor;
+
+        impl<'a> Visitor<'a> for PrefixStr16Visitor {
+            type Value = PrefixStr16;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(PrefixStr16::from(v))
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'a str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(PrefixStr16::from(v))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(PrefixStr16::from(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => Ok(PrefixStr16::from(s)),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'a [u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => Ok(PrefixStr16::from(s)),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match String::from_utf8(v) {
+                    Ok(s) => Ok(PrefixStr16::from(s)),
+                    Err(e) => Err(Error::invalid_value(
+                        Unexpected::Bytes(&e.into_bytes()),
+                        &self,
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(PrefixStr16Visitor)
+    }
+
+    impl serde::Serialize for PrefixStr16 {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            self.as_str().serialize(serializer)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'de> serde::Deserialize<'de> for PrefixStr16 {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            prefix_str16(deserializer)
+        }
+    }
+}
@@ -0,0 +1,13 @@
+/// The way in which the contents of a value are stored.
+///
+/// This is returned by the `kind` method of all the types in this crate.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Repr {
+    /// The contents are stored inline, within the value itself.
+    Inline,
+    /// The contents are borrowed from `'static` memory.
+    Static,
+    /// The contents are stored in a reference-counted heap allocation,
+    /// or in an owner kept alive by one, which may be shared with other values.
+    Heap,
+}
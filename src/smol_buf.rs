@@ -0,0 +1,1488 @@
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box, rc::Rc, sync::Arc, vec::Vec};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::iter;
+use core::marker::PhantomData;
+use core::mem::{ManuallyDrop, MaybeUninit};
+#[cfg(feature = "alloc")]
+use core::ptr::NonNull;
+use core::{fmt, hash, mem, ops, ptr, slice};
+
+#[cfg(feature = "alloc")]
+use crate::header;
+#[cfg(feature = "alloc")]
+use crate::owner::{self, Owner};
+#[cfg(feature = "alloc")]
+use crate::SmolAlloc;
+use crate::{ArcCounter, Counter, LengthError, RcCounter, Repr, TryReserveError};
+
+/// A small-data optimized byte buffer, which is `N` bytes large.
+///
+/// See [`SmolStr`](crate::SmolStr) for all the properties.
+#[repr(transparent)]
+pub struct SmolBuf<const N: usize, C: Counter = ArcCounter>(
+    <Size<N> as SupportedSize>::Raw,
+    PhantomData<C>,
+)
+where
+    Size<N>: SupportedSize;
+
+/// A [`SmolBuf`] with 15 bytes of inline storage.
+pub type Buf16 = SmolBuf<16>;
+/// A [`SmolBuf`] with 23 bytes of inline storage.
+pub type Buf24 = SmolBuf<24>;
+/// A [`SmolBuf`] with 31 bytes of inline storage.
+pub type Buf32 = SmolBuf<32>;
+
+/// The single-threaded counterpart of [`SmolBuf`], backed by `Rc<[u8]>` instead of `Arc<[u8]>`.
+///
+/// See [`LocalSmolStr`](crate::LocalSmolStr) for all the properties.
+pub type LocalSmolBuf<const N: usize> = SmolBuf<N, RcCounter>;
+
+/// A [`LocalSmolBuf`] with 15 bytes of inline storage.
+pub type LocalBuf16 = LocalSmolBuf<16>;
+/// A [`LocalSmolBuf`] with 23 bytes of inline storage.
+pub type LocalBuf24 = LocalSmolBuf<24>;
+/// A [`LocalSmolBuf`] with 31 bytes of inline storage.
+pub type LocalBuf32 = LocalSmolBuf<32>;
+
+/// The size in bytes of a [`SmolBuf`] or [`SmolStr`](crate::SmolStr).
+///
+/// This is only used in `where Size<N>: SupportedSize` bounds,
+/// which code generic over the size of these types has to repeat.
+pub struct Size<const N: usize>;
+
+/// Implemented for the [`Size`]s a [`SmolBuf`] can have, which are `16`, `24` and `32`.
+///
+/// The size has to be a multiple of 8 to leave no padding, and larger sizes would need
+/// more than the 5 bits next to the tag to store the inline length.
+pub trait SupportedSize: sealed::Sealed {
+    #[doc(hidden)]
+    type Raw: Copy + PartialEq;
+}
+
+mod sealed {
+    use core::num::NonZeroU8;
+
+    pub trait Sealed {}
+
+    /// The storage of a [`SmolBuf`](super::SmolBuf), which is a pointer and an array
+    /// of bytes followed by the tag.
+    ///
+    /// Inline buffers overwrite the pointer with their bytes. It is a real pointer
+    /// nonetheless, so that non-inline buffers keep the provenance of their pointer.
+    ///
+    /// The bytes following the pointer are sized for 8-byte pointers. With smaller pointers,
+    /// the tag would not be the last byte, which the size assertions reject.
+    #[repr(C, align(8))]
+    #[derive(Clone, Copy, PartialEq)]
+    pub struct Raw<B> {
+        ptr: *const u8,
+        buf: B,
+        pub(super) tag_and_len: NonZeroU8,
+    }
+}
+
+macro_rules! supported_size {
+    ($($n:literal),*) => {$(
+        impl sealed::Sealed for Size<$n> {}
+
+        impl SupportedSize for Size<$n> {
+            type Raw = sealed::Raw<[u8; $n - 9]>;
+        }
+
+        const _: () = {
+            assert!(mem::size_of::<SmolBuf<$n>>() == $n);
+            assert!(mem::align_of::<SmolBuf<$n>>() == 8);
+            assert!(mem::size_of::<Option<SmolBuf<$n>>>() == $n);
+            assert!(mem::size_of::<LocalSmolBuf<$n>>() == $n);
+            assert!(mem::offset_of!(<Size<$n> as SupportedSize>::Raw, tag_and_len) == $n - 1);
+        };
+    )*};
+}
+
+// The pointer is either `'static`, or refers to a thread-safe, reference-counted allocation
+// or owner. A `LocalSmolBuf` may hold an `Rc`, and stays `!Send` and `!Sync` like the pointer.
+unsafe impl<const N: usize> Send for SmolBuf<N> where Size<N>: SupportedSize {}
+unsafe impl<const N: usize> Sync for SmolBuf<N> where Size<N>: SupportedSize {}
+
+supported_size!(16, 24, 32);
+
+/// The largest inline capacity of all the supported sizes.
+#[cfg(feature = "alloc")]
+pub(crate) const MAX_INLINE_CAP: usize = 31;
+
+const TAG_INLINE: u8 = 0b001 << 5;
+const TAG_ARC: u8 = 0b010 << 5;
+const TAG_STATIC: u8 = 0b100 << 5;
+/// A sub-slice of an `Arc`, which is kept alive by the slice.
+#[cfg(feature = "alloc")]
+const TAG_SLICE: u8 = TAG_ARC | TAG_STATIC;
+/// An allocation created by [`header::try_alloc`]. These are never sliced.
+#[cfg(feature = "alloc")]
+const TAG_HEADER: u8 = TAG_ARC | TAG_INLINE;
+/// An external owner created by [`owner::new`], which `ptr` points to.
+///
+/// The bytes start at an offset into the bytes of the owner, which is stored like the
+/// offset of a `TAG_SLICE`.
+#[cfg(feature = "alloc")]
+const TAG_OWNER: u8 = TAG_ARC | TAG_STATIC | TAG_INLINE;
+/// The packed characters of a [`PackedStr24`](crate::PackedStr24), which never exposes them
+/// as a `SmolBuf`. Without the `TAG_ARC` bit, there is nothing to retain or free.
+const TAG_PACKED: u8 = TAG_STATIC | TAG_INLINE;
+const TAG_MASK: u8 = !(0b111 << 5);
+const TAG_MASK_FULL: u64 = !(0b111 << (64 - 3));
+const TAG_SHIFT: u8 = 64 - 8;
+
+/// The number of bytes following the length word which hold the offset and length
+/// of the parent `Arc` of a `TAG_SLICE`, or the offset into the owner of a `TAG_OWNER`,
+/// unless the length word is packed. Any further bytes before the tag stay zeroed.
+const SLICE_META_BYTES: usize = 7;
+
+impl<const N: usize, C: Counter> SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    /// The number of bytes that are stored inline.
+    pub const INLINE_CAP: usize = N - 1;
+
+    /// Whether the length shares a word with the tag, as there are no bytes left
+    /// in between them.
+    ///
+    /// A `TAG_SLICE` then packs its length, and the offset and length of its parent `Arc`
+    /// into that word. Otherwise, the offset and length of the parent are stored in the
+    /// bytes following the length.
+    const PACKED: bool = N == 16;
+
+    /// The number of bits used to encode the parent offset and length of a `TAG_SLICE`.
+    #[cfg(feature = "alloc")]
+    const SLICE_BITS: u32 = if Self::PACKED { 20 } else { 28 };
+    #[cfg(feature = "alloc")]
+    pub(crate) const SLICE_MAX: usize = (1 << Self::SLICE_BITS) - 1;
+
+    /// The number of bits used to encode the length of a packed `TAG_OWNER`,
+    /// whose offset is stored in the bits above it.
+    #[cfg(feature = "alloc")]
+    const OWNER_BITS: u32 = 30;
+    #[cfg(feature = "alloc")]
+    const OWNER_MAX: usize = (1 << Self::OWNER_BITS) - 1;
+
+    /// Constructs inline variant of `SmolBuf`.
+    ///
+    /// Panics if `input.len()` is larger than [`INLINE_CAP`](Self::INLINE_CAP).
+    #[inline]
+    pub const fn new_inline(input: &[u8]) -> Self {
+        let len = input.len();
+        assert!(len <= Self::INLINE_CAP); // avoids checks in loop
+
+        let mut raw = MaybeUninit::<Self>::zeroed();
+        let buf = raw.as_mut_ptr() as *mut u8;
+
+        let mut i = 0;
+        while i < len {
+            unsafe { buf.add(i).write(input[i]) };
+            i += 1
+        }
+
+        unsafe {
+            buf.add(N - 1).write(len as u8 | TAG_INLINE);
+            raw.assume_init()
+        }
+    }
+
+    /// Constructs a `SmolBuf` from a statically allocated slice.
+    ///
+    /// This never allocates, and can be used in a `const` context.
+    #[inline]
+    pub const fn new_static(input: &'static [u8]) -> Self {
+        let len = input.len();
+        if len <= Self::INLINE_CAP {
+            Self::new_inline(input)
+        } else {
+            Self::from_ptr(input.as_ptr(), len as u64, 0, TAG_STATIC)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub fn new(input: &[u8]) -> Self {
+        let len = input.len();
+        if len <= Self::INLINE_CAP {
+            Self::new_inline(input)
+        } else {
+            Self::new_counted(input)
+        }
+    }
+
+    /// Constructs a `SmolBuf`, returning an error if `input` does not fit inline,
+    /// and can not be allocated on the heap.
+    ///
+    /// Unlike `SmolBuf::new`, this does not abort the process if the allocation fails.
+    /// Without the `alloc` feature, this fails for every `input` longer than
+    /// [`INLINE_CAP`](Self::INLINE_CAP).
+    #[inline]
+    pub fn try_new(input: &[u8]) -> Result<Self, TryReserveError> {
+        if input.len() <= Self::INLINE_CAP {
+            return Ok(Self::new_inline(input));
+        }
+        #[cfg(feature = "alloc")]
+        return header::try_alloc(input, None).map(Self::from_header);
+        #[cfg(not(feature = "alloc"))]
+        Err(TryReserveError::disabled(input.len()))
+    }
+
+    /// Constructs a `SmolBuf`, allocating it through `alloc` if it does not fit inline.
+    ///
+    /// Panics or aborts like [`SmolBuf::new`] if the allocation fails.
+    #[cfg(feature = "alloc")]
+    pub fn new_in<A: SmolAlloc + 'static>(input: &[u8], alloc: &Arc<A>) -> Self {
+        Self::try_new_in(input, alloc).unwrap_or_else(|err| err.handle())
+    }
+
+    /// Constructs a `SmolBuf`, allocating it through `alloc` if it does not fit inline,
+    /// and returning an error if that allocation fails.
+    #[cfg(feature = "alloc")]
+    pub fn try_new_in<A: SmolAlloc + 'static>(
+        input: &[u8],
+        alloc: &Arc<A>,
+    ) -> Result<Self, TryReserveError> {
+        if input.len() <= Self::INLINE_CAP {
+            return Ok(Self::new_inline(input));
+        }
+        let alloc: Arc<dyn SmolAlloc> = alloc.clone();
+        header::try_alloc(input, Some(alloc)).map(Self::from_header)
+    }
+
+    /// Collects the bytes of `iter` into a `SmolBuf`, returning an error instead of
+    /// aborting if the bytes do not fit inline, and can not be allocated on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn try_from_iter<I: IntoIterator<Item = u8>>(iter: I) -> Result<Self, TryReserveError> {
+        let mut iter = iter.into_iter();
+        let mut buf = [0; MAX_INLINE_CAP];
+        let mut len = 0;
+        while let Some(byte) = iter.next() {
+            if len == Self::INLINE_CAP {
+                let mut heap = Vec::new();
+                header::try_extend(&mut heap, &buf[..len])?;
+                header::try_extend(&mut heap, &[byte])?;
+                for byte in iter {
+                    header::try_extend(&mut heap, &[byte])?;
+                }
+                return Self::try_new(&heap);
+            }
+            buf[len] = byte;
+            len += 1;
+        }
+        Ok(Self::new_inline(&buf[..len]))
+    }
+
+    /// Constructs a non-inline buffer from its pointer, length word and tag.
+    ///
+    /// `extra` is stored in the bytes following the length, unless it is packed.
+    #[inline]
+    const fn from_ptr(ptr: *const u8, len: u64, extra: u64, tag: u8) -> Self {
+        let mut raw = MaybeUninit::<Self>::zeroed();
+        let bytes = raw.as_mut_ptr() as *mut u8;
+        unsafe {
+            (bytes as *mut *const u8).write(ptr);
+            let len_word = bytes.add(8) as *mut u64;
+            if Self::PACKED {
+                len_word.write((len | ((tag as u64) << TAG_SHIFT)).to_le());
+            } else {
+                len_word.write(len.to_le());
+                let extra = extra.to_le_bytes();
+                ptr::copy_nonoverlapping(extra.as_ptr(), bytes.add(16), SLICE_META_BYTES);
+                bytes.add(N - 1).write(tag);
+            }
+            raw.assume_init()
+        }
+    }
+
+    /// Returns `true` if a non-inline buffer of `len` bytes fits the length encoding.
+    #[inline(always)]
+    pub(crate) const fn fits_len(len: usize) -> bool {
+        !Self::PACKED || len as u64 <= TAG_MASK_FULL
+    }
+
+    /// Returns the pointer of a non-inline buffer.
+    #[inline(always)]
+    pub(crate) fn ptr(&self) -> *const u8 {
+        unsafe { (self as *const Self as *const *const u8).read() }
+    }
+
+    /// Returns the word following the pointer, which holds the length of a non-inline buffer.
+    #[inline(always)]
+    fn len_word(&self) -> u64 {
+        let bytes = self as *const Self as *const u8;
+        u64::from_le(unsafe { (bytes.add(8) as *const u64).read() })
+    }
+
+    /// Returns the bytes following the length, which are only used by `TAG_SLICE`.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    fn extra(&self) -> u64 {
+        let mut extra = [0; 8];
+        let bytes = self as *const Self as *const u8;
+        unsafe { ptr::copy_nonoverlapping(bytes.add(16), extra.as_mut_ptr(), SLICE_META_BYTES) };
+        u64::from_le_bytes(extra)
+    }
+
+    /// Takes over the reference to `data`, which was created by [`header::try_alloc`].
+    ///
+    /// Allocations which are short enough to be stored inline are copied and released,
+    /// as only inline buffers can be this short.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn from_header(data: NonNull<u8>) -> Self {
+        let len = unsafe { header::len(data.as_ptr()) };
+        if len <= Self::INLINE_CAP {
+            let buf = Self::new_inline(unsafe { slice::from_raw_parts(data.as_ptr(), len) });
+            unsafe { header::release(data.as_ptr()) };
+            return buf;
+        }
+        // the packed length word only holds the tag, as the header knows the length
+        let len = if Self::PACKED { 0 } else { len as u64 };
+        Self::from_ptr(data.as_ptr(), len, 0, TAG_HEADER)
+    }
+
+    /// Constructs a `SmolBuf` viewing the bytes of `owner`, without copying them.
+    ///
+    /// The owner is moved into a reference-counted heap allocation, and dropped once the
+    /// last buffer viewing it is gone. This allows to wrap a `Vec<u8>`, or a memory-mapped
+    /// file, and to hand out [slices](SmolBuf::slice) of it which share the owner.
+    ///
+    /// The bytes are copied if they fit inline. A [`Buf16`] can only view owners smaller
+    /// than 1 GiB, and copies larger ones into a new allocation.
+    ///
+    /// Unlike the `From<Vec<u8>>` conversion, this keeps the spare capacity of a `Vec` alive.
+    #[cfg(feature = "alloc")]
+    pub fn from_owner<T: AsRef<[u8]> + Send + Sync + 'static>(owner: T) -> Self {
+        let len = owner.as_ref().len();
+        if len <= Self::INLINE_CAP || (Self::PACKED && len > Self::OWNER_MAX) {
+            return Self::new(owner.as_ref());
+        }
+        let owner = owner::new(owner);
+        let len = unsafe { owner::bytes(owner.as_ptr()) }.len();
+        Self::from_owner_range(owner, 0..len)
+    }
+
+    /// Takes over the reference to `owner`, viewing `range` of its bytes.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn from_owner_range(owner: NonNull<Owner>, range: ops::Range<usize>) -> Self {
+        let len = range.len();
+        let fits = !Self::PACKED || (len <= Self::OWNER_MAX && range.start <= Self::OWNER_MAX);
+        if len <= Self::INLINE_CAP || !fits {
+            let buf = Self::new(&unsafe { owner::bytes(owner.as_ptr()) }[range]);
+            unsafe { owner::release(owner.as_ptr()) };
+            return buf;
+        }
+
+        let ptr = owner.as_ptr() as *const u8;
+        let offset = range.start as u64;
+        if Self::PACKED {
+            Self::from_ptr(ptr, len as u64 | offset << Self::OWNER_BITS, 0, TAG_OWNER)
+        } else {
+            Self::from_ptr(ptr, len as u64, offset, TAG_OWNER)
+        }
+    }
+
+    /// Returns the owner keeping the bytes of this buffer alive,
+    /// and the offset of the bytes into those of the owner.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn owner(&self) -> Option<(*const Owner, usize)> {
+        if self.tag_byte() & !TAG_MASK != TAG_OWNER {
+            return None;
+        }
+        let offset = match Self::PACKED {
+            true => ((self.len_word() & TAG_MASK_FULL) >> Self::OWNER_BITS) as usize,
+            false => self.extra() as usize,
+        };
+        Some((self.ptr() as *const Owner, offset))
+    }
+
+    /// Decomposes a buffer viewing an owner into the owner,
+    /// and the range of its bytes that this buffer covers.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_owner_range(self) -> Result<(NonNull<Owner>, ops::Range<usize>), Self> {
+        let Some((owner, offset)) = self.owner() else {
+            return Err(self);
+        };
+        let range = offset..offset + self.len();
+        mem::forget(self);
+        Ok((
+            unsafe { NonNull::new_unchecked(owner as *mut Owner) },
+            range,
+        ))
+    }
+
+    #[cfg(feature = "alloc")]
+    #[cold]
+    fn new_counted(input: &[u8]) -> Self {
+        Self::from_counted(C::alloc(&[input]) as *const u8, input.len())
+    }
+
+    /// Takes over the reference to the reference-counted slice of `len` bytes at `ptr`.
+    ///
+    /// This is an `Arc`, or an `Rc` for a [`LocalSmolBuf`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn from_counted(ptr: *const u8, len: usize) -> Self {
+        Self::from_ptr(ptr, len as u64, 0, TAG_ARC)
+    }
+
+    /// Takes over the reference to the reference-counted slice of `parent_len` bytes
+    /// at `parent`, viewing `range` of it.
+    ///
+    /// `parent_len` must not be larger than `SLICE_MAX`.
+    #[cfg(feature = "alloc")]
+    fn from_counted_range(parent: *const u8, parent_len: usize, range: ops::Range<usize>) -> Self {
+        let meta = range.start as u64 | (parent_len as u64) << Self::SLICE_BITS;
+        let (ptr, len) = (parent.wrapping_add(range.start), range.len() as u64);
+        if Self::PACKED {
+            Self::from_ptr(ptr, len | meta << Self::SLICE_BITS, 0, TAG_SLICE)
+        } else {
+            Self::from_ptr(ptr, len, meta, TAG_SLICE)
+        }
+    }
+
+    /// Takes over the reference to an existing `Arc` or `Rc`, inlining it if it is
+    /// short enough.
+    ///
+    /// # Safety
+    ///
+    /// `counted` has to be a live reference-counted slice of the counter `C`.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    unsafe fn from_shared(counted: *const [u8]) -> Self {
+        let len = counted.len();
+        if len <= Self::INLINE_CAP {
+            let buf = Self::new_inline(&*counted);
+            C::release(counted);
+            buf
+        } else {
+            Self::from_counted(counted as *const u8, len)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_byte_iter<I: iter::Iterator<Item = u8>>(mut iter: I) -> Self {
+        let (min_size, _) = iter.size_hint();
+        if min_size > Self::INLINE_CAP {
+            let heap: Vec<u8> = iter.collect();
+            return Self::new(&heap);
+        }
+        let mut len = 0;
+        let mut buf = [0u8; MAX_INLINE_CAP];
+        while let Some(byte) = iter.next() {
+            if len == Self::INLINE_CAP {
+                let (min_remaining, _) = iter.size_hint();
+                let mut heap = Vec::with_capacity(len + 1 + min_remaining);
+                heap.extend_from_slice(&buf[..len]);
+                heap.push(byte);
+                heap.extend(iter);
+                return Self::new(&heap);
+            }
+            buf[len] = byte;
+            len += 1;
+        }
+        Self::new_inline(&buf[..len])
+    }
+
+    /// Returns a sub-slice of this buffer.
+    ///
+    /// Slices that fit are stored inline, and slices of a static buffer stay static.
+    /// Longer slices of heap-allocated buffers share the allocation with `self` instead
+    /// of copying, as long as that allocation was not created by [`SmolBuf::try_new`],
+    /// and is smaller than 1 MiB for a [`Buf16`], or 256 MiB for the larger sizes.
+    ///
+    /// Panics if the range is out of bounds.
+    pub fn slice(&self, range: impl ops::RangeBounds<usize>) -> Self {
+        let bytes = self.as_bytes();
+        let sub = &bytes[(range.start_bound().cloned(), range.end_bound().cloned())];
+        if sub.len() <= Self::INLINE_CAP {
+            return Self::new_inline(sub);
+        }
+        if sub.len() == bytes.len() {
+            return self.clone();
+        }
+
+        #[cfg(feature = "alloc")]
+        if let Some(parent) = self.counted() {
+            let start = sub.as_ptr() as usize - parent as *const u8 as usize;
+            return unsafe {
+                C::retain(parent);
+                Self::from_shared_range(parent, start..start + sub.len())
+            };
+        }
+        #[cfg(feature = "alloc")]
+        if let Some((owner, offset)) = self.owner() {
+            let start = offset + (sub.as_ptr() as usize - bytes.as_ptr() as usize);
+            unsafe { owner::retain(owner) };
+            let owner = unsafe { NonNull::new_unchecked(owner as *mut Owner) };
+            return Self::from_owner_range(owner, start..start + sub.len());
+        }
+        #[cfg(feature = "alloc")]
+        if self.header_data().is_some() {
+            return Self::new(sub);
+        }
+
+        // non-inline buffers without a backing `Arc`, `Rc` or owner are static
+        let sub: &'static [u8] = unsafe { &*(sub as *const [u8]) };
+        Self::new_static(sub)
+    }
+
+    /// Takes over the reference to `counted`, viewing `range` of it,
+    /// and shares it where possible.
+    ///
+    /// # Safety
+    ///
+    /// `counted` has to be a live reference-counted slice of the counter `C`.
+    #[cfg(feature = "alloc")]
+    unsafe fn from_shared_range(counted: *const [u8], range: ops::Range<usize>) -> Self {
+        let (len, parent_len) = (range.len(), counted.len());
+        if len == parent_len {
+            return Self::from_shared(counted);
+        }
+        if len <= Self::INLINE_CAP || parent_len > Self::SLICE_MAX {
+            let buf = Self::new(&(&*counted)[range]);
+            C::release(counted);
+            return buf;
+        }
+        Self::from_counted_range(counted as *const u8, parent_len, range)
+    }
+
+    /// Decomposes a heap-allocated buffer into its backing `Arc` or `Rc`,
+    /// and the range of it that this buffer covers.
+    #[cfg(feature = "alloc")]
+    fn into_counted_range(self) -> Result<(*const [u8], ops::Range<usize>), Self> {
+        let Some(counted) = self.counted() else {
+            return Err(self);
+        };
+        let start = self.as_ptr() as usize - counted as *const u8 as usize;
+        let range = start..start + self.len();
+        mem::forget(self);
+        Ok((counted, range))
+    }
+
+    /// Returns the reference-counted slice holding exactly the bytes of this buffer.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn counted_exact(&self) -> Option<*const [u8]> {
+        if self.tag_byte() & !TAG_MASK != TAG_ARC {
+            return None;
+        }
+        Some(ptr::slice_from_raw_parts(self.ptr(), self.heap_len()))
+    }
+
+    /// Returns the reference-counted slice keeping the bytes of this buffer alive,
+    /// which is the parent of a slice.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn counted(&self) -> Option<*const [u8]> {
+        let tag_byte = self.tag_byte();
+        let tag = tag_byte & !TAG_MASK;
+        if tag != TAG_ARC && tag != TAG_SLICE {
+            return None;
+        }
+
+        let (mut ptr, mut len) = (self.ptr(), self.heap_len());
+        if tag == TAG_SLICE {
+            let meta = match Self::PACKED {
+                true => (self.len_word() & TAG_MASK_FULL) >> Self::SLICE_BITS,
+                false => self.extra(),
+            };
+            ptr = ptr.wrapping_sub(meta as usize & Self::SLICE_MAX);
+            len = (meta >> Self::SLICE_BITS) as usize & Self::SLICE_MAX;
+        }
+        Some(ptr::slice_from_raw_parts(ptr, len))
+    }
+
+    /// Returns the pointer to the bytes of an allocation created by [`header::try_alloc`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn header_data(&self) -> Option<*const u8> {
+        if self.tag_byte() & !TAG_MASK != TAG_HEADER {
+            return None;
+        }
+        Some(self.ptr())
+    }
+
+    /// Decomposes a buffer created by [`header::try_alloc`] into its data pointer.
+    #[cfg(feature = "alloc")]
+    pub(crate) fn into_header(self) -> Result<NonNull<u8>, Self> {
+        let Some(data) = self.header_data() else {
+            return Err(self);
+        };
+        mem::forget(self);
+        Ok(unsafe { NonNull::new_unchecked(data as *mut u8) })
+    }
+
+    /// Converts this buffer into one with the reference count `D`.
+    ///
+    /// Only buffers backed by an `Arc` or `Rc` are copied, as the other kinds of storage
+    /// do not depend on the counter.
+    fn into_counter<D: Counter>(self) -> SmolBuf<N, D> {
+        #[cfg(feature = "alloc")]
+        if self.counted().is_some() {
+            return SmolBuf::new(&self);
+        }
+        SmolBuf(ManuallyDrop::new(self).0, PhantomData)
+    }
+
+    /// Returns the length of a non-inline buffer.
+    #[inline(always)]
+    fn heap_len(&self) -> usize {
+        if !Self::PACKED {
+            return self.len_word() as usize;
+        }
+        let len = (self.len_word() & TAG_MASK_FULL) as usize;
+        match self.tag_byte() & !TAG_MASK {
+            #[cfg(feature = "alloc")]
+            TAG_SLICE => len & Self::SLICE_MAX,
+            #[cfg(feature = "alloc")]
+            TAG_HEADER => unsafe { header::len(self.ptr()) },
+            #[cfg(feature = "alloc")]
+            TAG_OWNER => len & Self::OWNER_MAX,
+            _ => len,
+        }
+    }
+
+    #[inline(always)]
+    fn tag_byte(&self) -> u8 {
+        unsafe { *(self as *const Self as *const u8).add(N - 1) }
+    }
+
+    #[inline(always)]
+    pub(crate) fn is_inline(&self) -> bool {
+        self.tag_byte() & !TAG_MASK == TAG_INLINE
+    }
+
+    /// Compares two inline buffers a word at a time.
+    ///
+    /// The bytes are padded with zeros and followed by the tag and length, so the words
+    /// read as big-endian numbers order the same as the bytes they contain.
+    #[inline]
+    fn cmp_inline(&self, other: &Self) -> Ordering {
+        let words = self as *const Self as *const u64;
+        let other_words = other as *const Self as *const u64;
+        for i in 0..N / 8 {
+            let (word, other_word) = unsafe {
+                (
+                    u64::from_be(words.add(i).read()),
+                    u64::from_be(other_words.add(i).read()),
+                )
+            };
+            if word != other_word {
+                return word.cmp(&other_word);
+            }
+        }
+        Ordering::Equal
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        let tag_byte = self.tag_byte();
+        if tag_byte & !TAG_MASK == TAG_INLINE {
+            (tag_byte & TAG_MASK) as usize
+        } else {
+            self.heap_len()
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_heap_allocated(&self) -> bool {
+        self.tag_byte() & TAG_ARC > 0
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns how the contents of this buffer are stored.
+    #[inline]
+    pub fn kind(&self) -> Repr {
+        let tag_byte = self.tag_byte();
+        if tag_byte & !TAG_MASK == TAG_INLINE {
+            Repr::Inline
+        } else if tag_byte & TAG_ARC > 0 {
+            Repr::Heap
+        } else {
+            Repr::Static
+        }
+    }
+
+    /// Returns the number of values sharing the heap allocation of this buffer,
+    /// or `None` if it is not heap-allocated.
+    #[inline]
+    pub fn strong_count(&self) -> Option<usize> {
+        #[cfg(feature = "alloc")]
+        if let Some(data) = self.header_data() {
+            return Some(unsafe { header::strong_count(data) });
+        }
+        #[cfg(feature = "alloc")]
+        if let Some((owner, _)) = self.owner() {
+            return Some(unsafe { owner::strong_count(owner) });
+        }
+        #[cfg(feature = "alloc")]
+        return self
+            .counted()
+            .map(|counted| unsafe { C::strong_count(counted) });
+        #[cfg(not(feature = "alloc"))]
+        None
+    }
+
+    /// Returns `true` if both buffers refer to the same non-inline memory.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.kind() != Repr::Inline && self.as_ptr() == other.as_ptr() && self.len() == other.len()
+    }
+
+    /// Returns the underlying `&'static` slice, if this buffer was created from one.
+    ///
+    /// This returns `None` for static slices that were short enough to be stored inline.
+    #[inline]
+    pub fn as_static(&self) -> Option<&'static [u8]> {
+        if self.kind() != Repr::Static {
+            return None;
+        }
+        let bytes = self.as_bytes();
+        Some(unsafe { &*(bytes as *const [u8]) })
+    }
+
+    #[inline(always)]
+    pub fn as_bytes(&self) -> &[u8] {
+        let tag_byte = self.tag_byte();
+        let (ptr, len) = if tag_byte & !TAG_MASK == TAG_INLINE {
+            (
+                self as *const _ as *const u8,
+                (tag_byte & TAG_MASK) as usize,
+            )
+        } else {
+            #[cfg(feature = "alloc")]
+            if let Some((owner, offset)) = self.owner() {
+                let bytes = unsafe { owner::bytes(owner) };
+                return &bytes[offset..][..self.heap_len()];
+            }
+            (self.ptr(), self.heap_len())
+        };
+        unsafe { slice::from_raw_parts(ptr, len) }
+    }
+
+    /// Converts this buffer into a `SmolBuf` of another size, moving over its heap allocation.
+    ///
+    /// Inline buffers which are too long to be stored inline in a `SmolBuf<M>` are copied
+    /// to the heap. This fails if that is not possible, or if the length does not fit the
+    /// length encoding of a [`Buf16`].
+    pub fn try_convert<const M: usize>(self) -> Result<SmolBuf<M, C>, LengthError<Self>>
+    where
+        Size<M>: SupportedSize,
+    {
+        if !SmolBuf::<M, C>::fits_len(self.len()) {
+            return Err(LengthError(self));
+        }
+        if let Some(bytes) = self.as_static() {
+            return Ok(SmolBuf::new_static(bytes));
+        }
+        #[cfg(feature = "alloc")]
+        let buf = match self.into_counted_range() {
+            Ok((counted, range)) => {
+                return Ok(unsafe { SmolBuf::from_shared_range(counted, range) })
+            }
+            Err(buf) => buf,
+        };
+        #[cfg(feature = "alloc")]
+        let buf = match buf.into_header() {
+            Ok(data) => return Ok(SmolBuf::from_header(data)),
+            Err(buf) => buf,
+        };
+        #[cfg(feature = "alloc")]
+        let buf = match buf.into_owner_range() {
+            Ok((owner, range)) => return Ok(SmolBuf::from_owner_range(owner, range)),
+            Err(buf) => buf,
+        };
+        #[cfg(not(feature = "alloc"))]
+        let buf = self;
+        SmolBuf::try_new(&buf).map_err(|_| LengthError(buf))
+    }
+
+    /// Returns a mutable slice of the bytes of this buffer.
+    ///
+    /// Inline buffers and heap buffers which are not shared are mutated in place.
+    /// Static and shared buffers are copied into a new heap allocation first.
+    #[cfg(feature = "alloc")]
+    pub fn make_mut(&mut self) -> &mut [u8] {
+        let len = self.len();
+        if self.is_inline() {
+            return &mut self.inline_mut()[..len];
+        }
+
+        let is_unique = match self.header_data() {
+            Some(data) => unsafe { header::make_mut(data) },
+            None => self
+                .counted()
+                .is_some_and(|counted| unsafe { C::is_unique(counted) }),
+        };
+        if !is_unique {
+            *self = Self::from_parts(&[self.as_bytes()]);
+        }
+        unsafe { slice::from_raw_parts_mut(self.ptr() as *mut u8, len) }
+    }
+
+    /// Appends a byte to the end of the buffer.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn push(&mut self, byte: u8) {
+        self.extend_from_slice(&[byte]);
+    }
+
+    /// Appends the given bytes to the end of the buffer.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn extend_from_slice(&mut self, bytes: &[u8]) {
+        self.insert_slice(self.len(), bytes);
+    }
+
+    /// Inserts the given bytes at position `idx`.
+    ///
+    /// This happens in place if the result still fits inline.
+    /// Otherwise, the result is copied into a new heap allocation.
+    ///
+    /// Panics if `idx > len`.
+    #[cfg(feature = "alloc")]
+    pub fn insert_slice(&mut self, idx: usize, bytes: &[u8]) {
+        let len = self.len();
+        assert!(
+            idx <= len,
+            "insertion index (is {idx}) should be <= len (is {len})"
+        );
+        if bytes.is_empty() {
+            return;
+        }
+
+        if len + bytes.len() <= Self::INLINE_CAP {
+            // only inline buffers can be this short
+            self.insert_inline(idx, bytes);
+            return;
+        }
+
+        let current = self.as_bytes();
+        *self = Self::from_parts(&[&current[..idx], bytes, &current[idx..]]);
+    }
+
+    /// Inserts the given bytes at position `idx` of an inline buffer,
+    /// which still fits inline afterwards.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub(crate) fn insert_inline(&mut self, idx: usize, bytes: &[u8]) {
+        let len = self.len();
+        let new_len = len + bytes.len();
+        debug_assert!(self.is_inline() && new_len <= Self::INLINE_CAP);
+        let inline = self.inline_mut();
+        inline.copy_within(idx..len, idx + bytes.len());
+        inline[idx..][..bytes.len()].copy_from_slice(bytes);
+        inline[N - 1] = new_len as u8 | TAG_INLINE;
+    }
+
+    /// Shortens the buffer to `new_len` bytes.
+    ///
+    /// A heap-allocated buffer keeps sharing its allocation, see [`SmolBuf::slice`].
+    /// This has no effect if `new_len` is greater than the current length.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            *self = self.slice(..new_len);
+        }
+    }
+
+    /// Truncates the buffer to an empty one.
+    #[inline]
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    /// Concatenates the given parts into a new buffer.
+    #[cfg(feature = "alloc")]
+    #[cold]
+    fn from_parts(parts: &[&[u8]]) -> Self {
+        let len = parts.iter().map(|part| part.len()).sum();
+        if len <= Self::INLINE_CAP {
+            let mut buf = [0; MAX_INLINE_CAP];
+            let mut offset = 0;
+            for part in parts {
+                buf[offset..][..part.len()].copy_from_slice(part);
+                offset += part.len();
+            }
+            return Self::new_inline(&buf[..len]);
+        }
+
+        Self::from_counted(C::alloc(parts) as *const u8, len)
+    }
+
+    /// Returns all the bytes of an inline buffer, the last one being the tag and length.
+    #[cfg(feature = "alloc")]
+    #[inline(always)]
+    pub(crate) fn inline_mut(&mut self) -> &mut [u8] {
+        unsafe { slice::from_raw_parts_mut(self as *mut Self as *mut u8, N) }
+    }
+}
+
+impl SmolBuf<24> {
+    /// Constructs a `TAG_PACKED` buffer of `len` characters from the words of a
+    /// [`PackedStr24`](crate::PackedStr24), whose top bytes are overwritten by the tag.
+    #[inline]
+    pub(crate) fn from_packed(words: [u64; 3], len: usize) -> Self {
+        debug_assert!(len <= TAG_MASK as usize);
+        let mut raw = MaybeUninit::<Self>::zeroed();
+        let bytes = raw.as_mut_ptr() as *mut u8;
+        for (i, word) in words.iter().enumerate() {
+            let word = word.to_le_bytes();
+            unsafe { ptr::copy_nonoverlapping(word.as_ptr(), bytes.add(i * 8), 8) };
+        }
+        unsafe {
+            bytes.add(23).write(len as u8 | TAG_PACKED);
+            raw.assume_init()
+        }
+    }
+
+    /// Returns the words and the length of a `TAG_PACKED` buffer, without the tag.
+    #[inline]
+    pub(crate) fn packed(&self) -> Option<([u64; 3], usize)> {
+        let tag_byte = self.tag_byte();
+        if tag_byte & !TAG_MASK != TAG_PACKED {
+            return None;
+        }
+        let bytes = self as *const Self as *const u8;
+        let mut words = [0; 3];
+        for (i, word) in words.iter_mut().enumerate() {
+            let mut le = [0; 8];
+            unsafe { ptr::copy_nonoverlapping(bytes.add(i * 8), le.as_mut_ptr(), 8) };
+            *word = u64::from_le_bytes(le);
+        }
+        words[2] &= u64::MAX >> 8;
+        Some((words, (tag_byte & TAG_MASK) as usize))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> Drop for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn drop(&mut self) {
+        if let Some(data) = self.header_data() {
+            unsafe { header::release(data) };
+        } else if let Some((owner, _)) = self.owner() {
+            unsafe { owner::release(owner) };
+        } else if let Some(counted) = self.counted() {
+            unsafe { C::release(counted) };
+        }
+    }
+}
+
+impl<const N: usize, C: Counter> Clone for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn clone(&self) -> Self {
+        #[cfg(feature = "alloc")]
+        if let Some(data) = self.header_data() {
+            unsafe { header::retain(data) };
+        } else if let Some((owner, _)) = self.owner() {
+            unsafe { owner::retain(owner) };
+        } else if let Some(counted) = self.counted() {
+            unsafe { C::retain(counted) };
+        }
+
+        Self(self.0, PhantomData)
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &Self) -> bool {
+        if self.0 == other.0 {
+            return true;
+        }
+        // inline buffers are padded with zeros, so they are only equal if all their words are
+        if self.is_inline() && other.is_inline() {
+            return false;
+        }
+        self.as_bytes() == other.as_bytes()
+    }
+}
+
+impl<const N: usize, C: Counter> Eq for SmolBuf<N, C> where Size<N>: SupportedSize {}
+
+impl<const N: usize, C: Counter> Default for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn default() -> Self {
+        Self::new_inline(&[])
+    }
+}
+
+impl<const N: usize, C: Counter> ops::Deref for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    type Target = [u8];
+
+    #[inline(always)]
+    fn deref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq<[u8]> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &[u8]) -> bool {
+        self.as_bytes() == other
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq<SmolBuf<N, C>> for [u8]
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolBuf<N, C>) -> bool {
+        other == self
+    }
+}
+
+impl<'a, const N: usize, C: Counter> PartialEq<&'a [u8]> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &&'a [u8]) -> bool {
+        self == *other
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq<SmolBuf<N, C>> for &[u8]
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolBuf<N, C>) -> bool {
+        *self == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> PartialEq<Vec<u8>> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &Vec<u8>) -> bool {
+        self.as_bytes() == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> PartialEq<SmolBuf<N, C>> for Vec<u8>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolBuf<N, C>) -> bool {
+        other == self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, const N: usize, C: Counter> PartialEq<&'a Vec<u8>> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &&'a Vec<u8>) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> PartialEq<SmolBuf<N, C>> for &Vec<u8>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolBuf<N, C>) -> bool {
+        *self == other
+    }
+}
+
+impl<const N: usize, C: Counter> Ord for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        if self.is_inline() && other.is_inline() {
+            return self.cmp_inline(other);
+        }
+        self.as_bytes().cmp(other.as_bytes())
+    }
+}
+
+impl<const N: usize, C: Counter> PartialOrd for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize, C: Counter> hash::Hash for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        self.as_bytes().hash(hasher);
+    }
+}
+
+impl<const N: usize, C: Counter> fmt::Debug for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_bytes(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> iter::FromIterator<u8> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn from_iter<I: iter::IntoIterator<Item = u8>>(iter: I) -> Self {
+        Self::from_byte_iter(iter.into_iter())
+    }
+}
+
+impl<const N: usize, C: Counter> AsRef<[u8]> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn as_ref(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<&[u8]> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(b: &[u8]) -> Self {
+        Self::new(b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<&mut [u8]> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(b: &mut [u8]) -> Self {
+        Self::new(b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<&Vec<u8>> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(b: &Vec<u8>) -> Self {
+        Self::new(b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<Vec<u8>> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    /// Converts a `Vec<u8>` into a `SmolBuf`, copying bytes which do not fit inline
+    /// into a new allocation without spare capacity.
+    ///
+    /// Use [`SmolBuf::from_owner`] to take over the allocation of the `Vec` instead.
+    #[inline(always)]
+    fn from(b: Vec<u8>) -> Self {
+        Self::new(&b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<Box<[u8]>> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(b: Box<[u8]>) -> Self {
+        Self::new(&b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, const N: usize, C: Counter> From<Cow<'a, [u8]>> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(b: Cow<'a, [u8]>) -> Self {
+        Self::new(&b)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<SmolBuf<N, C>> for Vec<u8>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn from(buf: SmolBuf<N, C>) -> Self {
+        buf.as_bytes().into()
+    }
+}
+
+impl<const N: usize, C: Counter> Borrow<[u8]> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn borrow(&self) -> &[u8] {
+        self.as_bytes()
+    }
+}
+
+/// Implements the conversions from and into the `Arc` or `Rc` of a [`Counter`],
+/// which share the allocation instead of copying it.
+macro_rules! convert_shared {
+    ($($counter:ty => $rc:ident),*) => {$(
+        #[cfg(feature = "alloc")]
+        impl<const N: usize> From<$rc<[u8]>> for SmolBuf<N, $counter>
+        where
+            Size<N>: SupportedSize,
+        {
+            #[inline]
+            fn from(b: $rc<[u8]>) -> Self {
+                unsafe { Self::from_shared($rc::into_raw(b)) }
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl<const N: usize> From<SmolBuf<N, $counter>> for $rc<[u8]>
+        where
+            Size<N>: SupportedSize,
+        {
+            #[inline(always)]
+            fn from(buf: SmolBuf<N, $counter>) -> Self {
+                if let Some(counted) = buf.counted_exact() {
+                    mem::forget(buf);
+                    return unsafe { $rc::from_raw(counted) };
+                }
+                $rc::from(buf.as_bytes())
+            }
+        }
+    )*};
+}
+
+convert_shared!(ArcCounter => Arc, RcCounter => Rc);
+
+impl<const N: usize> From<SmolBuf<N>> for LocalSmolBuf<N>
+where
+    Size<N>: SupportedSize,
+{
+    /// Converts a [`SmolBuf`] into a [`LocalSmolBuf`].
+    ///
+    /// Buffers backed by an `Arc` are copied into a new `Rc`. All other buffers are moved
+    /// over as they are, including those created by [`SmolBuf::try_new`], whose reference
+    /// count stays atomic.
+    #[inline]
+    fn from(buf: SmolBuf<N>) -> Self {
+        buf.into_counter()
+    }
+}
+
+impl<const N: usize> From<LocalSmolBuf<N>> for SmolBuf<N>
+where
+    Size<N>: SupportedSize,
+{
+    /// Converts a [`LocalSmolBuf`] into a [`SmolBuf`].
+    ///
+    /// Buffers backed by an `Rc` are copied into a new `Arc`. All other buffers are moved
+    /// over as they are, including those created by [`LocalSmolBuf::try_new`].
+    #[inline]
+    fn from(buf: LocalSmolBuf<N>) -> Self {
+        buf.into_counter()
+    }
+}
+
+/// Implements `From` for conversions into a larger size, which can not fail.
+macro_rules! convert_from_smaller {
+    ($($from:literal => $to:literal),*) => {$(
+        impl<C: Counter> From<SmolBuf<$from, C>> for SmolBuf<$to, C> {
+            /// Converts a buffer into a larger one, moving over its heap allocation.
+            #[inline]
+            fn from(buf: SmolBuf<$from, C>) -> Self {
+                match buf.try_convert() {
+                    Ok(buf) => buf,
+                    Err(_) => unreachable!("the inline bytes fit into the larger size"),
+                }
+            }
+        }
+    )*};
+}
+
+convert_from_smaller!(16 => 24, 16 => 32, 24 => 32);
+
+/// Implements `TryFrom` for conversions into a smaller size.
+macro_rules! convert_from_larger {
+    ($($from:literal => $to:literal),*) => {$(
+        impl<C: Counter> TryFrom<SmolBuf<$from, C>> for SmolBuf<$to, C> {
+            type Error = LengthError<SmolBuf<$from, C>>;
+
+            /// Converts a buffer into a smaller one, moving over its heap allocation.
+            ///
+            /// See [`SmolBuf::try_convert`].
+            #[inline]
+            fn try_from(buf: SmolBuf<$from, C>) -> Result<Self, Self::Error> {
+                buf.try_convert()
+            }
+        }
+    )*};
+}
+
+convert_from_larger!(24 => 16, 32 => 16, 32 => 24);
+
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize, C: Counter> arbitrary::Arbitrary<'a> for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> Result<Self, arbitrary::Error> {
+        let b = <&[u8]>::arbitrary(u)?;
+        Self::try_new(b).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    #[cfg(feature = "alloc")]
+    use alloc::vec::Vec;
+    #[cfg(feature = "alloc")]
+    use core::fmt;
+    #[cfg(feature = "alloc")]
+    use core::marker::PhantomData;
+
+    #[cfg(feature = "alloc")]
+    use serde::de::{Deserializer, Error, SeqAccess, Visitor};
+
+    #[cfg(feature = "alloc")]
+    use super::MAX_INLINE_CAP;
+    use super::{Counter, Size, SmolBuf, SupportedSize};
+
+    #[cfg(feature = "alloc")]
+    fn smol_buf<'de: 'a, 'a, D, const N: usize, C: Counter>(
+        deserializer: D,
+    ) -> Result<SmolBuf<N, C>, D::Error>
+    where
+        D: Deserializer<'de>,
+        Size<N>: SupportedSize,
+    {
+        struct SmolBufVisitor<const N: usize, C>(PhantomData<C>);
+
+        impl<'a, const N: usize, C: Counter> Visitor<'a> for SmolBufVisitor<N, C>
+        where
+            Size<N>: SupportedSize,
+        {
+            type Value = SmolBuf<N, C>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a byte array")
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(SmolBuf::from(v))
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'a [u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(SmolBuf::from(v))
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(SmolBuf::from(v))
+            }
+
+            fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+            where
+                A: SeqAccess<'a>,
+            {
+                let mut len = 0;
+                let mut buf = [0u8; MAX_INLINE_CAP];
+                while let Some(byte) = seq.next_element()? {
+                    if len == SmolBuf::<N>::INLINE_CAP {
+                        let mut heap = Vec::with_capacity(2 * len);
+                        heap.extend_from_slice(&buf[..len]);
+                        heap.push(byte);
+                        while let Some(byte) = seq.next_element()? {
+                            heap.push(byte);
+                        }
+                        return Ok(SmolBuf::from(heap));
+                    }
+                    buf[len] = byte;
+                    len += 1;
+                }
+                Ok(SmolBuf::new_inline(&buf[..len]))
+            }
+        }
+
+        deserializer.deserialize_bytes(SmolBufVisitor(PhantomData))
+    }
+
+    impl<const N: usize, C: Counter> serde::Serialize for SmolBuf<N, C>
+    where
+        Size<N>: SupportedSize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            serializer.serialize_bytes(self.as_bytes())
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'de, const N: usize, C: Counter> serde::Deserialize<'de> for SmolBuf<N, C>
+    where
+        Size<N>: SupportedSize,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            smol_buf(deserializer)
+        }
+    }
+}
@@ -0,0 +1,1193 @@
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box, rc::Rc, string::String, sync::Arc};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::convert::Infallible;
+#[cfg(feature = "alloc")]
+use core::marker::PhantomData;
+use core::ops::{Deref, RangeBounds};
+use core::str::from_utf8_unchecked;
+#[cfg(feature = "alloc")]
+use core::str::{from_utf8_unchecked_mut, FromStr};
+use core::{fmt, hash};
+#[cfg(feature = "alloc")]
+use core::{iter, mem};
+
+#[cfg(feature = "alloc")]
+use crate::smol_buf::MAX_INLINE_CAP;
+#[cfg(feature = "alloc")]
+use crate::SmolAlloc;
+use crate::{
+    ArcCounter, Counter, FromUtf8Error, LengthError, RcCounter, Repr, Size, SmolBuf, SupportedSize,
+    TryReserveError,
+};
+
+/// A `SmolStr` is a string type that has the following properties:
+///
+/// * `size_of::<SmolStr<N>>() == N`
+/// * `size_of::<Option<SmolStr<N>>>() == size_of::<SmolStr<N>>()`
+/// * `Clone` is `O(1)`
+/// * Strings are stack-allocated if they are up to `N - 1` bytes long
+/// * If a string does not satisfy the aforementioned conditions, it is heap-allocated
+/// * Additionally, a `SmolStr` can be explicitly created from a `&'static str` without allocation
+///
+/// Unlike `String`, however, `SmolStr` has no spare capacity. Its mutating methods
+/// are copy-on-write, and reallocate whenever a heap-allocated string grows.
+///
+/// A `SmolStr` hashes exactly like the `str` it borrows as, so a `HashMap` with the default
+/// hasher treats it like a `String` key. Only maps built with [`BuildWordHasher`](crate::BuildWordHasher) hash
+/// the inline strings as a fixed number of words.
+///
+/// The supported sizes are `16`, `24` and `32`, see [`SupportedSize`].
+/// Code which is generic over the size has to repeat the `Size<N>: SupportedSize` bound.
+pub struct SmolStr<const N: usize, C: Counter = ArcCounter>(pub(crate) SmolBuf<N, C>)
+where
+    Size<N>: SupportedSize;
+
+/// A [`SmolStr`] with 15 bytes of inline storage.
+///
+/// Its size is the same as `size_of::<Arc<str>>()` on 64 bit platforms.
+pub type Str16 = SmolStr<16>;
+/// A [`SmolStr`] with 23 bytes of inline storage.
+///
+/// Its size is the same as `size_of::<String>()` on 64 bit platforms.
+pub type Str24 = SmolStr<24>;
+/// A [`SmolStr`] with 31 bytes of inline storage.
+pub type Str32 = SmolStr<32>;
+
+/// The single-threaded counterpart of [`SmolStr`].
+///
+/// A `LocalSmolStr` has the same layout and API as a [`SmolStr`], but heap-allocated strings
+/// are backed by `Rc<str>` instead of `Arc<str>`. `Clone` and `Drop` therefore do not need
+/// atomic operations, but a `LocalSmolStr` is neither `Send` nor `Sync`.
+///
+/// Strings created by [`LocalSmolStr::try_new`] still use an atomic reference count,
+/// and can be converted to and from a [`SmolStr`] without copying.
+pub type LocalSmolStr<const N: usize> = SmolStr<N, RcCounter>;
+
+/// A [`LocalSmolStr`] with 15 bytes of inline storage.
+pub type LocalStr16 = LocalSmolStr<16>;
+/// A [`LocalSmolStr`] with 23 bytes of inline storage.
+pub type LocalStr24 = LocalSmolStr<24>;
+/// A [`LocalSmolStr`] with 31 bytes of inline storage.
+pub type LocalStr32 = LocalSmolStr<32>;
+
+impl<const N: usize, C: Counter> SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    /// The number of bytes that are stored inline.
+    pub const INLINE_CAP: usize = SmolBuf::<N>::INLINE_CAP;
+
+    /// Constructs inline variant of `SmolStr`.
+    ///
+    /// Panics if `text.len()` is larger than [`INLINE_CAP`](Self::INLINE_CAP).
+    #[inline]
+    pub const fn new_inline(text: &str) -> Self {
+        Self(SmolBuf::new_inline(text.as_bytes()))
+    }
+
+    /// Constructs a `SmolStr` from a statically allocated string.
+    ///
+    /// This never allocates, and can be used in a `const` context.
+    #[inline]
+    pub const fn new_static(text: &'static str) -> Self {
+        Self(SmolBuf::new_static(text.as_bytes()))
+    }
+
+    #[inline]
+    #[cfg(feature = "alloc")]
+    pub fn new<T>(text: T) -> Self
+    where
+        T: AsRef<str>,
+    {
+        Self(SmolBuf::new(text.as_ref().as_bytes()))
+    }
+
+    /// Constructs a `SmolStr`, returning an error if `text` does not fit inline,
+    /// and can not be allocated on the heap.
+    ///
+    /// See [`SmolBuf::try_new`].
+    #[inline]
+    pub fn try_new<T>(text: T) -> Result<Self, TryReserveError>
+    where
+        T: AsRef<str>,
+    {
+        SmolBuf::try_new(text.as_ref().as_bytes()).map(Self)
+    }
+
+    /// Constructs a `SmolStr`, allocating it through `alloc` if it does not fit inline.
+    ///
+    /// See [`SmolBuf::new_in`].
+    #[cfg(feature = "alloc")]
+    pub fn new_in<T, A>(text: T, alloc: &Arc<A>) -> Self
+    where
+        T: AsRef<str>,
+        A: SmolAlloc + 'static,
+    {
+        Self(SmolBuf::new_in(text.as_ref().as_bytes(), alloc))
+    }
+
+    /// Constructs a `SmolStr`, allocating it through `alloc` if it does not fit inline,
+    /// and returning an error if that allocation fails.
+    #[cfg(feature = "alloc")]
+    pub fn try_new_in<T, A>(text: T, alloc: &Arc<A>) -> Result<Self, TryReserveError>
+    where
+        T: AsRef<str>,
+        A: SmolAlloc + 'static,
+    {
+        SmolBuf::try_new_in(text.as_ref().as_bytes(), alloc).map(Self)
+    }
+
+    /// Constructs a `SmolStr` viewing the string of `owner`, without copying it.
+    ///
+    /// This takes over the allocation of a `String` or `Box<str>`, which the `From`
+    /// conversions copy. See [`SmolBuf::from_owner`].
+    #[cfg(feature = "alloc")]
+    pub fn from_owner<T: AsRef<str> + Send + Sync + 'static>(owner: T) -> Self {
+        Self(SmolBuf::from_owner(StrOwner(owner)))
+    }
+
+    /// Constructs a `SmolStr` from a string slice, returning an error if it does not fit
+    /// inline, and can not be allocated on the heap.
+    ///
+    /// This is the fallible counterpart of the `FromStr` implementation.
+    #[inline]
+    pub fn try_from_str(text: &str) -> Result<Self, TryReserveError> {
+        Self::try_new(text)
+    }
+
+    /// Collects the `char`s of `iter` into a `SmolStr`, returning an error instead of
+    /// aborting if they do not fit inline, and can not be allocated on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn try_from_iter<I: IntoIterator<Item = char>>(iter: I) -> Result<Self, TryReserveError> {
+        let mut builder = SmolStrBuilder::new();
+        for ch in iter {
+            builder.try_push_str(ch.encode_utf8(&mut [0; 4]))?;
+        }
+        builder.try_finish()
+    }
+
+    /// Converts a [`SmolBuf`] into a `SmolStr`, keeping its storage as it is.
+    ///
+    /// Returns an error containing the original buffer if it is not valid UTF-8.
+    #[inline]
+    pub fn from_utf8(bytes: SmolBuf<N, C>) -> Result<Self, FromUtf8Error<SmolBuf<N, C>>> {
+        match core::str::from_utf8(&bytes) {
+            Ok(_) => Ok(Self(bytes)),
+            Err(error) => Err(FromUtf8Error { bytes, error }),
+        }
+    }
+
+    /// Converts a [`SmolBuf`] into a `SmolStr` without checking that it is valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The bytes of the buffer must be valid UTF-8.
+    #[inline]
+    pub const unsafe fn from_utf8_unchecked(bytes: SmolBuf<N, C>) -> Self {
+        Self(bytes)
+    }
+
+    /// Converts this string into a [`SmolBuf`], keeping its storage as it is.
+    #[inline]
+    pub fn into_bytes(self) -> SmolBuf<N, C> {
+        self.0
+    }
+
+    /// Returns the underlying [`SmolBuf`] of this string.
+    #[inline(always)]
+    pub fn as_buf(&self) -> &SmolBuf<N, C> {
+        &self.0
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        unsafe { from_utf8_unchecked(self.0.as_bytes()) }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    #[inline(always)]
+    pub fn to_string(&self) -> String {
+        use alloc::borrow::ToOwned;
+
+        self.as_str().to_owned()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_heap_allocated(&self) -> bool {
+        self.0.is_heap_allocated()
+    }
+
+    /// Returns how the contents of this string are stored.
+    #[inline]
+    pub fn kind(&self) -> Repr {
+        self.0.kind()
+    }
+
+    /// Returns the number of values sharing the heap allocation of this string,
+    /// or `None` if it is not heap-allocated.
+    #[inline]
+    pub fn strong_count(&self) -> Option<usize> {
+        self.0.strong_count()
+    }
+
+    /// Returns `true` if both strings refer to the same non-inline memory.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.0.ptr_eq(&other.0)
+    }
+
+    /// Returns the underlying `&'static str`, if this string was created from one.
+    ///
+    /// This returns `None` for static strings that were short enough to be stored inline.
+    #[inline]
+    pub fn as_static(&self) -> Option<&'static str> {
+        let bytes = self.0.as_static()?;
+        Some(unsafe { from_utf8_unchecked(bytes) })
+    }
+
+    /// Converts this string into a `SmolStr` of another size, moving over its heap allocation.
+    ///
+    /// See [`SmolBuf::try_convert`].
+    #[inline]
+    pub fn try_convert<const M: usize>(self) -> Result<SmolStr<M, C>, LengthError<Self>>
+    where
+        Size<M>: SupportedSize,
+    {
+        match self.0.try_convert() {
+            Ok(buf) => Ok(SmolStr(buf)),
+            Err(err) => Err(LengthError(Self(err.0))),
+        }
+    }
+
+    /// Returns a substring of this string.
+    ///
+    /// The substring shares the heap allocation of `self` where possible,
+    /// see [`SmolBuf::slice`].
+    ///
+    /// Panics if the range is out of bounds or does not lie on `char` boundaries.
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Self {
+        let sub = &self.as_str()[(range.start_bound().cloned(), range.end_bound().cloned())];
+        let start = sub.as_ptr() as usize - self.as_ptr() as usize;
+        Self(self.0.slice(start..start + sub.len()))
+    }
+
+    /// Returns a mutable string slice of this string.
+    ///
+    /// This copies static and shared strings first, see [`SmolBuf::make_mut`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn make_mut(&mut self) -> &mut str {
+        unsafe { from_utf8_unchecked_mut(self.0.make_mut()) }
+    }
+
+    /// Appends the given `char` to the end of this string.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Appends the given string slice to the end of this string.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    /// Inserts a string slice into this string at byte position `idx`.
+    ///
+    /// This happens in place if the result still fits inline,
+    /// see [`SmolBuf::insert_slice`].
+    ///
+    /// Panics if `idx` is larger than the length, or does not lie on a `char` boundary.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn insert_str(&mut self, idx: usize, s: &str) {
+        assert!(self.is_char_boundary(idx));
+        self.0.insert_slice(idx, s.as_bytes());
+    }
+
+    /// Shortens this string to `new_len` bytes.
+    ///
+    /// This has no effect if `new_len` is greater than the current length.
+    ///
+    /// Panics if `new_len` does not lie on a `char` boundary.
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(self.is_char_boundary(new_len));
+            self.0.truncate(new_len);
+        }
+    }
+
+    /// Truncates this string to an empty one.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_char_iter<I: iter::Iterator<Item = char>>(iter: I) -> Self {
+        let (min_size, _) = iter.size_hint();
+        if min_size > Self::INLINE_CAP {
+            let heap: String = iter.collect();
+            return Self::new(heap);
+        }
+        let mut builder = SmolStrBuilder::new();
+        iter.for_each(|ch| builder.push(ch));
+        builder.finish()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_str_iter<T>(iter: impl Iterator<Item = T>) -> Self
+    where
+        T: AsRef<str>,
+    {
+        let mut builder = SmolStrBuilder::new();
+        iter.for_each(|slice| builder.push_str(slice.as_ref()));
+        builder.finish()
+    }
+}
+
+impl<const N: usize, C: Counter> Deref for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq<str> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq<SmolStr<N, C>> for str
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolStr<N, C>) -> bool {
+        other == self
+    }
+}
+
+impl<'a, const N: usize, C: Counter> PartialEq<&'a str> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq<SmolStr<N, C>> for &str
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolStr<N, C>) -> bool {
+        *self == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> PartialEq<String> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> PartialEq<SmolStr<N, C>> for String
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolStr<N, C>) -> bool {
+        other == self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, const N: usize, C: Counter> PartialEq<&'a String> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &&'a String) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> PartialEq<SmolStr<N, C>> for &String
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &SmolStr<N, C>) -> bool {
+        *self == other
+    }
+}
+
+impl<const N: usize, C: Counter> Clone for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<const N: usize, C: Counter> Default for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn default() -> Self {
+        Self(SmolBuf::default())
+    }
+}
+
+impl<const N: usize, C: Counter> PartialEq for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl<const N: usize, C: Counter> Eq for SmolStr<N, C> where Size<N>: SupportedSize {}
+
+impl<const N: usize, C: Counter> Ord for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn cmp(&self, other: &Self) -> Ordering {
+        // UTF-8 strings order the same as their bytes
+        self.0.cmp(&other.0)
+    }
+}
+
+impl<const N: usize, C: Counter> PartialOrd for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<const N: usize, C: Counter> hash::Hash for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        self.as_str().hash(hasher);
+    }
+}
+
+impl<const N: usize, C: Counter> fmt::Debug for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl<const N: usize, C: Counter> fmt::Display for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> iter::FromIterator<char> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn from_iter<I: iter::IntoIterator<Item = char>>(iter: I) -> Self {
+        Self::from_char_iter(iter.into_iter())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> iter::FromIterator<String> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn from_iter<I: iter::IntoIterator<Item = String>>(iter: I) -> Self {
+        Self::from_str_iter(iter.into_iter())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, const N: usize, C: Counter> iter::FromIterator<&'a String> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn from_iter<I: iter::IntoIterator<Item = &'a String>>(iter: I) -> Self {
+        Self::from_str_iter(iter.into_iter().map(|x| x.as_str()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, const N: usize, C: Counter> iter::FromIterator<&'a str> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn from_iter<I: iter::IntoIterator<Item = &'a str>>(iter: I) -> Self {
+        Self::from_str_iter(iter.into_iter())
+    }
+}
+
+impl<const N: usize, C: Counter> AsRef<str> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<&str> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(s: &str) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<&mut str> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(s: &mut str) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<&String> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(s: &String) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<String> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    /// Converts a `String` into a `SmolStr`, copying strings which do not fit inline
+    /// into a new allocation without spare capacity.
+    ///
+    /// Use [`SmolStr::from_owner`] to take over the allocation of the `String` instead.
+    #[inline(always)]
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<Box<str>> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(s: Box<str>) -> Self {
+        Self::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a, const N: usize, C: Counter> From<Cow<'a, str>> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(s: Cow<'a, str>) -> Self {
+        Self::new(s)
+    }
+}
+
+/// Exposes the bytes of a string owner to [`SmolBuf::from_owner`].
+#[cfg(feature = "alloc")]
+struct StrOwner<T>(T);
+
+#[cfg(feature = "alloc")]
+impl<T: AsRef<str>> AsRef<[u8]> for StrOwner<T> {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_ref().as_bytes()
+    }
+}
+
+/// Implements `From` for conversions into a larger size, which can not fail.
+macro_rules! convert_from_smaller {
+    ($($from:literal => $to:literal),*) => {$(
+        impl<C: Counter> From<SmolStr<$from, C>> for SmolStr<$to, C> {
+            /// Converts a string into a larger one, moving over its heap allocation.
+            #[inline]
+            fn from(text: SmolStr<$from, C>) -> Self {
+                Self(SmolBuf::from(text.0))
+            }
+        }
+    )*};
+}
+
+convert_from_smaller!(16 => 24, 16 => 32, 24 => 32);
+
+/// Implements `TryFrom` for conversions into a smaller size.
+macro_rules! convert_from_larger {
+    ($($from:literal => $to:literal),*) => {$(
+        impl<C: Counter> TryFrom<SmolStr<$from, C>> for SmolStr<$to, C> {
+            type Error = LengthError<SmolStr<$from, C>>;
+
+            /// Converts a string into a smaller one, moving over its heap allocation.
+            ///
+            /// See [`SmolBuf::try_convert`].
+            #[inline]
+            fn try_from(text: SmolStr<$from, C>) -> Result<Self, Self::Error> {
+                text.try_convert()
+            }
+        }
+    )*};
+}
+
+convert_from_larger!(24 => 16, 32 => 16, 32 => 24);
+
+/// Implements the conversions from and into the `Arc` or `Rc` of a [`Counter`],
+/// which share the allocation instead of copying it.
+macro_rules! convert_shared {
+    ($($counter:ty => $rc:ident),*) => {$(
+        #[cfg(feature = "alloc")]
+        impl<const N: usize> From<$rc<str>> for SmolStr<N, $counter>
+        where
+            Size<N>: SupportedSize,
+        {
+            #[inline]
+            fn from(s: $rc<str>) -> Self {
+                let bytes = unsafe { mem::transmute::<$rc<str>, $rc<[u8]>>(s) };
+                Self(SmolBuf::from(bytes))
+            }
+        }
+
+        #[cfg(feature = "alloc")]
+        impl<const N: usize> From<SmolStr<N, $counter>> for $rc<str>
+        where
+            Size<N>: SupportedSize,
+        {
+            #[inline(always)]
+            fn from(text: SmolStr<N, $counter>) -> Self {
+                let bytes = $rc::<[u8]>::from(text.0);
+                unsafe { mem::transmute::<$rc<[u8]>, $rc<str>>(bytes) }
+            }
+        }
+    )*};
+}
+
+convert_shared!(ArcCounter => Arc, RcCounter => Rc);
+
+impl<const N: usize> From<SmolStr<N>> for LocalSmolStr<N>
+where
+    Size<N>: SupportedSize,
+{
+    /// Converts a [`SmolStr`] into a [`LocalSmolStr`], see [`LocalSmolBuf::from`](crate::LocalSmolBuf).
+    #[inline]
+    fn from(text: SmolStr<N>) -> Self {
+        Self(text.0.into())
+    }
+}
+
+impl<const N: usize> From<LocalSmolStr<N>> for SmolStr<N>
+where
+    Size<N>: SupportedSize,
+{
+    /// Converts a [`LocalSmolStr`] into a [`SmolStr`], see [`SmolBuf::from`].
+    #[inline]
+    fn from(text: LocalSmolStr<N>) -> Self {
+        Self(text.0.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<SmolStr<N, C>> for String
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn from(text: SmolStr<N, C>) -> Self {
+        text.as_str().into()
+    }
+}
+
+impl<const N: usize, C: Counter> Borrow<str> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> FromStr for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    type Err = Infallible;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(Self::from(s))
+    }
+}
+
+/// A builder to incrementally construct a [`SmolStr`].
+///
+/// The string is kept in a buffer on the stack as long as it fits inline,
+/// and is only moved to the heap once it outgrows that buffer.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct SmolStrBuilder<const N: usize, C: Counter = ArcCounter>(
+    SmolStrBuilderRepr,
+    PhantomData<C>,
+);
+
+/// A builder for a [`Str16`].
+#[cfg(feature = "alloc")]
+pub type Str16Builder = SmolStrBuilder<16>;
+/// A builder for a [`Str24`].
+#[cfg(feature = "alloc")]
+pub type Str24Builder = SmolStrBuilder<24>;
+/// A builder for a [`Str32`].
+#[cfg(feature = "alloc")]
+pub type Str32Builder = SmolStrBuilder<32>;
+
+/// A builder to incrementally construct a [`LocalSmolStr`].
+#[cfg(feature = "alloc")]
+pub type LocalSmolStrBuilder<const N: usize> = SmolStrBuilder<N, RcCounter>;
+/// A builder for a [`LocalStr16`].
+#[cfg(feature = "alloc")]
+pub type LocalStr16Builder = LocalSmolStrBuilder<16>;
+/// A builder for a [`LocalStr24`].
+#[cfg(feature = "alloc")]
+pub type LocalStr24Builder = LocalSmolStrBuilder<24>;
+/// A builder for a [`LocalStr32`].
+#[cfg(feature = "alloc")]
+pub type LocalStr32Builder = LocalSmolStrBuilder<32>;
+
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug)]
+enum SmolStrBuilderRepr {
+    Inline {
+        len: usize,
+        buf: [u8; MAX_INLINE_CAP],
+    },
+    Heap(String),
+}
+
+#[cfg(feature = "alloc")]
+impl Default for SmolStrBuilderRepr {
+    #[inline]
+    fn default() -> Self {
+        SmolStrBuilderRepr::Inline {
+            len: 0,
+            buf: [0; MAX_INLINE_CAP],
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> SmolStrBuilder<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    /// Creates a new, empty builder.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(
+            SmolStrBuilderRepr::Inline {
+                len: 0,
+                buf: [0; MAX_INLINE_CAP],
+            },
+            PhantomData,
+        )
+    }
+
+    /// Appends the given `char` to the string.
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Appends the given string slice to the string.
+    pub fn push_str(&mut self, s: &str) {
+        match &mut self.0 {
+            SmolStrBuilderRepr::Inline { len, buf } => {
+                let size = s.len();
+                if *len + size > SmolStr::<N>::INLINE_CAP {
+                    let mut heap = String::with_capacity(*len + size);
+                    heap.push_str(unsafe { from_utf8_unchecked(&buf[..*len]) });
+                    heap.push_str(s);
+                    self.0 = SmolStrBuilderRepr::Heap(heap);
+                } else {
+                    buf[*len..][..size].copy_from_slice(s.as_bytes());
+                    *len += size;
+                }
+            }
+            SmolStrBuilderRepr::Heap(heap) => heap.push_str(s),
+        }
+    }
+
+    /// Appends the given string slice to the string, returning an error instead of
+    /// aborting if the string can not grow.
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        let new_len = s.len()
+            + match &self.0 {
+                SmolStrBuilderRepr::Inline { len, .. } => *len,
+                SmolStrBuilderRepr::Heap(heap) => heap.len(),
+            };
+        let reserved = match &mut self.0 {
+            SmolStrBuilderRepr::Inline { .. } if new_len <= SmolStr::<N>::INLINE_CAP => Ok(()),
+            SmolStrBuilderRepr::Inline { len, buf } => {
+                let mut heap = String::new();
+                let reserved = heap.try_reserve_exact(new_len);
+                if reserved.is_ok() {
+                    heap.push_str(unsafe { from_utf8_unchecked(&buf[..*len]) });
+                    self.0 = SmolStrBuilderRepr::Heap(heap);
+                }
+                reserved
+            }
+            SmolStrBuilderRepr::Heap(heap) => heap.try_reserve(s.len()),
+        };
+        if reserved.is_err() {
+            return Err(TryReserveError::alloc_error(new_len));
+        }
+
+        // this does not allocate anymore
+        self.push_str(s);
+        Ok(())
+    }
+
+    /// Consumes the builder and returns the finished [`SmolStr`].
+    pub fn finish(self) -> SmolStr<N, C> {
+        self.finish_with(SmolStr::new_inline, SmolStr::new)
+    }
+
+    /// Consumes the builder and returns the finished [`SmolStr`], returning an error
+    /// instead of aborting if it can not be allocated on the heap.
+    pub fn try_finish(self) -> Result<SmolStr<N, C>, TryReserveError> {
+        self.finish_with(|text| Ok(SmolStr::new_inline(text)), SmolStr::try_new)
+    }
+
+    /// Consumes the builder, passing the string to `inline` if it fits inline,
+    /// or to `heap` otherwise.
+    #[inline]
+    pub(crate) fn finish_with<T>(
+        self,
+        inline: impl FnOnce(&str) -> T,
+        heap: impl FnOnce(String) -> T,
+    ) -> T {
+        match self.0 {
+            SmolStrBuilderRepr::Inline { len, buf } => {
+                inline(unsafe { from_utf8_unchecked(&buf[..len]) })
+            }
+            SmolStrBuilderRepr::Heap(string) => heap(string),
+        }
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> fmt::Write for SmolStrBuilder<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<const N: usize, C: Counter> From<SmolStrBuilder<N, C>> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    #[inline]
+    fn from(builder: SmolStrBuilder<N, C>) -> Self {
+        builder.finish()
+    }
+}
+
+/// Formats the arguments into a [`Str16`].
+///
+/// This works like [`format!`](alloc::format), but writes into an inline buffer
+/// and only allocates if the result is longer than the inline capacity.
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! format_str16 {
+    ($($tt:tt)*) => {{
+        use ::core::fmt::Write;
+        let mut builder = $crate::Str16Builder::new();
+        builder
+            .write_fmt(::core::format_args!($($tt)*))
+            .expect("a formatting trait implementation returned an error");
+        builder.finish()
+    }};
+}
+
+/// Formats the arguments into a [`Str24`].
+///
+/// This works like [`format!`](alloc::format), but writes into an inline buffer
+/// and only allocates if the result is longer than the inline capacity.
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! format_str24 {
+    ($($tt:tt)*) => {{
+        use ::core::fmt::Write;
+        let mut builder = $crate::Str24Builder::new();
+        builder
+            .write_fmt(::core::format_args!($($tt)*))
+            .expect("a formatting trait implementation returned an error");
+        builder.finish()
+    }};
+}
+
+/// Formats the arguments into a [`Str32`].
+///
+/// This works like [`format!`](alloc::format), but writes into an inline buffer
+/// and only allocates if the result is longer than the inline capacity.
+#[cfg(feature = "alloc")]
+#[macro_export]
+macro_rules! format_str32 {
+    ($($tt:tt)*) => {{
+        use ::core::fmt::Write;
+        let mut builder = $crate::Str32Builder::new();
+        builder
+            .write_fmt(::core::format_args!($($tt)*))
+            .expect("a formatting trait implementation returned an error");
+        builder.finish()
+    }};
+}
+
+/// A trait for converting a value to a [`Str16`].
+///
+/// This is the [`Str16`] equivalent of [`ToString`](alloc::string::ToString),
+/// and is implemented for every type implementing [`Display`](fmt::Display).
+#[cfg(feature = "alloc")]
+pub trait ToStr16 {
+    fn to_str16(&self) -> Str16;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Display + ?Sized> ToStr16 for T {
+    #[inline]
+    fn to_str16(&self) -> Str16 {
+        format_str16!("{}", self)
+    }
+}
+
+/// A trait for converting a value to a [`Str24`].
+///
+/// This is the [`Str24`] equivalent of [`ToString`](alloc::string::ToString),
+/// and is implemented for every type implementing [`Display`](fmt::Display).
+#[cfg(feature = "alloc")]
+pub trait ToStr24 {
+    fn to_str24(&self) -> Str24;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Display + ?Sized> ToStr24 for T {
+    #[inline]
+    fn to_str24(&self) -> Str24 {
+        format_str24!("{}", self)
+    }
+}
+
+/// A trait for converting a value to a [`Str32`].
+///
+/// This is the [`Str32`] equivalent of [`ToString`](alloc::string::ToString),
+/// and is implemented for every type implementing [`Display`](fmt::Display).
+#[cfg(feature = "alloc")]
+pub trait ToStr32 {
+    fn to_str32(&self) -> Str32;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: fmt::Display + ?Sized> ToStr32 for T {
+    #[inline]
+    fn to_str32(&self) -> Str32 {
+        format_str32!("{}", self)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a, const N: usize, C: Counter> arbitrary::Arbitrary<'a> for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> Result<Self, arbitrary::Error> {
+        let s = <&str>::arbitrary(u)?;
+        Self::try_new(s).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    #[cfg(feature = "alloc")]
+    use alloc::{string::String, vec::Vec};
+    #[cfg(feature = "alloc")]
+    use core::fmt;
+    #[cfg(feature = "alloc")]
+    use core::marker::PhantomData;
+
+    #[cfg(feature = "alloc")]
+    use serde::de::{Deserializer, Error, Unexpected, Visitor};
+
+    use crate::{Counter, Size, SmolStr, SupportedSize};
+
+    #[cfg(feature = "alloc")]
+    // https://github.com/serde-rs/serde/blob/629802f2abfd1a54a6072992888fea7ca5bc209f/serde/src/private/de.rs#L56-L125
+    fn smol_str<'de: 'a, 'a, D, const N: usize, C: Counter>(
+        deserializer: D,
+    ) -> Result<SmolStr<N, C>, D::Error>
+    where
+        D: Deserializer<'de>,
+        Size<N>: SupportedSize,
+    {
+        struct SmolStrVisitor<const N: usize, C>(PhantomData<C>);
+
+        impl<'a, const N: usize, C: Counter> Visitor<'a> for SmolStrVisitor<N, C>
+        where
+            Size<N>: SupportedSize,
+        {
+            type Value = SmolStr<N, C>;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(SmolStr::from(v))
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'a str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(SmolStr::from(v))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(SmolStr::from(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => Ok(SmolStr::from(s)),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'a [u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => Ok(SmolStr::from(s)),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match String::from_utf8(v) {
+                    Ok(s) => Ok(SmolStr::from(s)),
+                    Err(e) => Err(Error::invalid_value(
+                        Unexpected::Bytes(&e.into_bytes()),
+                        &self,
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(SmolStrVisitor(PhantomData))
+    }
+
+    impl<const N: usize, C: Counter> serde::Serialize for SmolStr<N, C>
+    where
+        Size<N>: SupportedSize,
+    {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            self.as_str().serialize(serializer)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'de, const N: usize, C: Counter> serde::Deserialize<'de> for SmolStr<N, C>
+    where
+        Size<N>: SupportedSize,
+    {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            smol_str(deserializer)
+        }
+    }
+}
@@ -0,0 +1,712 @@
+#[cfg(feature = "alloc")]
+use alloc::{borrow::Cow, boxed::Box, string::String, sync::Arc};
+use core::borrow::Borrow;
+use core::cmp::Ordering;
+#[cfg(feature = "alloc")]
+use core::convert::Infallible;
+#[cfg(feature = "alloc")]
+use core::iter;
+use core::ops::Deref;
+#[cfg(feature = "alloc")]
+use core::ops::RangeBounds;
+use core::str::from_utf8_unchecked;
+#[cfg(feature = "alloc")]
+use core::str::{from_utf8_unchecked_mut, FromStr};
+use core::{fmt, hash};
+
+#[cfg(feature = "alloc")]
+use crate::buf8::INLINE_CAP;
+#[cfg(feature = "alloc")]
+use crate::Str16Builder;
+use crate::{Buf8, FromUtf8Error, Repr, SmolStr, Str16, Str24, TryReserveError};
+
+/// A `Str8` is a string type that has the following properties:
+///
+/// * `size_of::<Str8>() == 8` (therefor `== size_of::<usize>()` on 64 bit platforms)
+/// * `size_of::<Option<Str8>>() == size_of::<Str8>()`
+/// * `Clone` is `O(1)`
+/// * Strings are stack-allocated if they are up to 7 bytes long
+/// * If a string does not satisfy the aforementioned conditions, it is heap-allocated,
+///   with its length stored in front of the bytes, behind a thin pointer
+/// * Additionally, a `Str8` can be explicitly created from a `&'static &'static str`
+///   without allocation
+///
+/// Unlike `String`, however, `Str8` has no spare capacity. Its mutating methods
+/// are copy-on-write, and reallocate whenever a heap-allocated string grows.
+///
+/// Like a [`SmolStr`](crate::SmolStr), it hashes like a `str`, and only gets the faster
+/// hashing of inline strings in maps using [`BuildWordHasher`](crate::BuildWordHasher).
+#[derive(Clone, Default, PartialEq, Eq)]
+pub struct Str8(pub(crate) Buf8);
+
+impl Str8 {
+    /// Constructs inline variant of `Str8`.
+    ///
+    /// Panics if `text.len() > 7`.
+    #[inline]
+    pub const fn new_inline(text: &str) -> Str8 {
+        Self(Buf8::new_inline(text.as_bytes()))
+    }
+
+    /// Constructs a `Str8` from a reference to a statically allocated string.
+    ///
+    /// This never allocates, see [`Buf8::new_static`].
+    #[inline]
+    pub fn new_static(text: &'static &'static str) -> Str8 {
+        let bytes = unsafe { &*(text as *const &str as *const &[u8]) };
+        Self(Buf8::new_static(bytes))
+    }
+
+    #[cfg(feature = "alloc")]
+    pub fn new<T>(text: T) -> Str8
+    where
+        T: AsRef<str>,
+    {
+        Str8(Buf8::new(text.as_ref().as_bytes()))
+    }
+
+    /// Constructs a `Str8`, returning an error if `text` does not fit inline,
+    /// and can not be allocated on the heap.
+    ///
+    /// See [`Buf8::try_new`].
+    #[inline]
+    pub fn try_new<T>(text: T) -> Result<Str8, TryReserveError>
+    where
+        T: AsRef<str>,
+    {
+        Buf8::try_new(text.as_ref().as_bytes()).map(Str8)
+    }
+
+    /// Constructs a `Str8` from a string slice, returning an error if it does not fit
+    /// inline, and can not be allocated on the heap.
+    ///
+    /// This is the fallible counterpart of the `FromStr` implementation.
+    #[inline]
+    pub fn try_from_str(text: &str) -> Result<Str8, TryReserveError> {
+        Self::try_new(text)
+    }
+
+    /// Collects the `char`s of `iter` into a `Str8`, returning an error instead of
+    /// aborting if they do not fit inline, and can not be allocated on the heap.
+    #[cfg(feature = "alloc")]
+    pub fn try_from_iter<I: IntoIterator<Item = char>>(iter: I) -> Result<Str8, TryReserveError> {
+        let mut builder = Str8Builder::new();
+        for ch in iter {
+            builder.try_push_str(ch.encode_utf8(&mut [0; 4]))?;
+        }
+        builder.try_finish()
+    }
+
+    /// Converts a [`Buf8`] into a `Str8`, keeping its storage as it is.
+    ///
+    /// Returns an error containing the original buffer if it is not valid UTF-8.
+    #[inline]
+    pub fn from_utf8(bytes: Buf8) -> Result<Str8, FromUtf8Error<Buf8>> {
+        match core::str::from_utf8(&bytes) {
+            Ok(_) => Ok(Str8(bytes)),
+            Err(error) => Err(FromUtf8Error { bytes, error }),
+        }
+    }
+
+    /// Converts a [`Buf8`] into a `Str8` without checking that it is valid UTF-8.
+    ///
+    /// # Safety
+    ///
+    /// The bytes of the buffer must be valid UTF-8.
+    #[inline]
+    pub const unsafe fn from_utf8_unchecked(bytes: Buf8) -> Str8 {
+        Str8(bytes)
+    }
+
+    /// Converts this string into a [`Buf8`], keeping its storage as it is.
+    #[inline]
+    pub fn into_bytes(self) -> Buf8 {
+        self.0
+    }
+
+    /// Returns the underlying [`Buf8`] of this string.
+    #[inline(always)]
+    pub fn as_buf(&self) -> &Buf8 {
+        &self.0
+    }
+
+    #[inline(always)]
+    pub fn as_str(&self) -> &str {
+        unsafe { from_utf8_unchecked(self.0.as_bytes()) }
+    }
+
+    #[cfg(feature = "alloc")]
+    #[allow(clippy::inherent_to_string_shadow_display)]
+    #[inline(always)]
+    pub fn to_string(&self) -> String {
+        use alloc::borrow::ToOwned;
+
+        self.as_str().to_owned()
+    }
+
+    #[inline(always)]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    #[inline(always)]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline(always)]
+    pub fn is_heap_allocated(&self) -> bool {
+        self.0.is_heap_allocated()
+    }
+
+    /// Returns how the contents of this string are stored.
+    #[inline]
+    pub fn kind(&self) -> Repr {
+        self.0.kind()
+    }
+
+    /// Returns the number of values sharing the heap allocation of this string,
+    /// or `None` if it is not heap-allocated.
+    #[inline]
+    pub fn strong_count(&self) -> Option<usize> {
+        self.0.strong_count()
+    }
+
+    /// Returns `true` if both strings refer to the same non-inline memory.
+    #[inline]
+    pub fn ptr_eq(&self, other: &Str8) -> bool {
+        self.0.ptr_eq(&other.0)
+    }
+
+    /// Returns the underlying `&'static str`, if this string was created from one.
+    ///
+    /// This returns `None` for static strings that were short enough to be stored inline.
+    #[inline]
+    pub fn as_static(&self) -> Option<&'static str> {
+        let bytes = self.0.as_static()?;
+        Some(unsafe { from_utf8_unchecked(bytes) })
+    }
+
+    /// Returns a substring of this string.
+    ///
+    /// Longer substrings are copied, see [`Buf8::slice`](crate::Buf8::slice).
+    ///
+    /// Panics if the range is out of bounds or does not lie on `char` boundaries.
+    #[cfg(feature = "alloc")]
+    pub fn slice(&self, range: impl RangeBounds<usize>) -> Str8 {
+        let sub = &self.as_str()[(range.start_bound().cloned(), range.end_bound().cloned())];
+        let start = sub.as_ptr() as usize - self.as_ptr() as usize;
+        Str8(self.0.slice(start..start + sub.len()))
+    }
+
+    /// Returns a mutable string slice of this string.
+    ///
+    /// This copies static and shared strings first, see [`Buf8::make_mut`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn make_mut(&mut self) -> &mut str {
+        unsafe { from_utf8_unchecked_mut(self.0.make_mut()) }
+    }
+
+    /// Appends the given `char` to the end of this string.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.push_str(ch.encode_utf8(&mut [0; 4]));
+    }
+
+    /// Appends the given string slice to the end of this string.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.0.extend_from_slice(s.as_bytes());
+    }
+
+    /// Inserts a string slice into this string at byte position `idx`.
+    ///
+    /// This happens in place if the result still fits inline,
+    /// see [`Buf8::insert_slice`].
+    ///
+    /// Panics if `idx` is larger than the length, or does not lie on a `char` boundary.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn insert_str(&mut self, idx: usize, s: &str) {
+        assert!(self.is_char_boundary(idx));
+        self.0.insert_slice(idx, s.as_bytes());
+    }
+
+    /// Shortens this string to `new_len` bytes.
+    ///
+    /// This has no effect if `new_len` is greater than the current length.
+    ///
+    /// Panics if `new_len` does not lie on a `char` boundary.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    pub fn truncate(&mut self, new_len: usize) {
+        if new_len < self.len() {
+            assert!(self.is_char_boundary(new_len));
+            self.0.truncate(new_len);
+        }
+    }
+
+    /// Truncates this string to an empty one.
+    #[inline]
+    pub fn clear(&mut self) {
+        self.0.clear();
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_char_iter<I: iter::Iterator<Item = char>>(iter: I) -> Str8 {
+        let (min_size, _) = iter.size_hint();
+        if min_size > INLINE_CAP {
+            let heap: String = iter.collect();
+            return Self::new(heap);
+        }
+        let mut builder = Str8Builder::new();
+        iter.for_each(|ch| builder.push(ch));
+        builder.finish()
+    }
+
+    #[cfg(feature = "alloc")]
+    fn from_str_iter<T>(iter: impl Iterator<Item = T>) -> Str8
+    where
+        T: AsRef<str>,
+    {
+        let mut builder = Str8Builder::new();
+        iter.for_each(|slice| builder.push_str(slice.as_ref()));
+        builder.finish()
+    }
+}
+
+impl Deref for Str8 {
+    type Target = str;
+
+    #[inline(always)]
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl PartialEq<str> for Str8 {
+    fn eq(&self, other: &str) -> bool {
+        self.as_str() == other
+    }
+}
+
+impl PartialEq<Str8> for str {
+    fn eq(&self, other: &Str8) -> bool {
+        other == self
+    }
+}
+
+impl<'a> PartialEq<&'a str> for Str8 {
+    fn eq(&self, other: &&'a str) -> bool {
+        self == *other
+    }
+}
+
+impl PartialEq<Str8> for &str {
+    fn eq(&self, other: &Str8) -> bool {
+        *self == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<String> for Str8 {
+    fn eq(&self, other: &String) -> bool {
+        self.as_str() == other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<Str8> for String {
+    fn eq(&self, other: &Str8) -> bool {
+        other == self
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> PartialEq<&'a String> for Str8 {
+    fn eq(&self, other: &&'a String) -> bool {
+        self == *other
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl PartialEq<Str8> for &String {
+    fn eq(&self, other: &Str8) -> bool {
+        *self == other
+    }
+}
+
+impl Ord for Str8 {
+    fn cmp(&self, other: &Str8) -> Ordering {
+        self.as_str().cmp(other.as_str())
+    }
+}
+
+impl PartialOrd for Str8 {
+    fn partial_cmp(&self, other: &Str8) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl hash::Hash for Str8 {
+    fn hash<H: hash::Hasher>(&self, hasher: &mut H) {
+        self.as_str().hash(hasher);
+    }
+}
+
+impl fmt::Debug for Str8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl fmt::Display for Str8 {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(self.as_str(), f)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl iter::FromIterator<char> for Str8 {
+    fn from_iter<I: iter::IntoIterator<Item = char>>(iter: I) -> Str8 {
+        Self::from_char_iter(iter.into_iter())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl iter::FromIterator<String> for Str8 {
+    fn from_iter<I: iter::IntoIterator<Item = String>>(iter: I) -> Str8 {
+        Self::from_str_iter(iter.into_iter())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> iter::FromIterator<&'a String> for Str8 {
+    fn from_iter<I: iter::IntoIterator<Item = &'a String>>(iter: I) -> Str8 {
+        Self::from_str_iter(iter.into_iter().map(|x| x.as_str()))
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> iter::FromIterator<&'a str> for Str8 {
+    fn from_iter<I: iter::IntoIterator<Item = &'a str>>(iter: I) -> Str8 {
+        Self::from_str_iter(iter.into_iter())
+    }
+}
+
+impl AsRef<str> for Str8 {
+    #[inline(always)]
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&str> for Str8 {
+    #[inline]
+    fn from(s: &str) -> Str8 {
+        Str8::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&mut str> for Str8 {
+    #[inline]
+    fn from(s: &mut str) -> Str8 {
+        Str8::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<&String> for Str8 {
+    #[inline]
+    fn from(s: &String) -> Str8 {
+        Str8::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<String> for Str8 {
+    #[inline(always)]
+    fn from(text: String) -> Self {
+        Self::new(text)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Box<str>> for Str8 {
+    #[inline]
+    fn from(s: Box<str>) -> Str8 {
+        Str8::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Arc<str>> for Str8 {
+    #[inline]
+    fn from(s: Arc<str>) -> Str8 {
+        Str8::new(s)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl<'a> From<Cow<'a, str>> for Str8 {
+    #[inline]
+    fn from(s: Cow<'a, str>) -> Str8 {
+        Str8::new(s)
+    }
+}
+
+impl From<Str8> for Str16 {
+    /// Converts a [`Str8`] into a [`Str16`], moving over its heap allocation.
+    #[inline]
+    fn from(text: Str8) -> Self {
+        SmolStr(text.0.into())
+    }
+}
+
+impl From<Str8> for Str24 {
+    /// Converts a [`Str8`] into a [`Str24`], moving over its heap allocation.
+    #[inline]
+    fn from(text: Str8) -> Self {
+        SmolStr(text.0.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Str16> for Str8 {
+    /// Converts a [`Str16`] into a [`Str8`].
+    ///
+    /// Strings created by [`Str16::try_new`] have their allocation moved over,
+    /// all other strings which do not fit inline are copied.
+    #[inline]
+    fn from(text: Str16) -> Self {
+        Str8(text.0.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Str24> for Str8 {
+    /// Converts a [`Str24`] into a [`Str8`].
+    ///
+    /// Strings created by [`Str24::try_new`] or [`Str24::new_in`] have their allocation
+    /// moved over, all other strings which do not fit inline are copied.
+    #[inline]
+    fn from(text: Str24) -> Self {
+        Str8(text.0.into())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Str8> for Arc<str> {
+    #[inline(always)]
+    fn from(text: Str8) -> Self {
+        Arc::from(text.as_str())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Str8> for String {
+    #[inline(always)]
+    fn from(text: Str8) -> Self {
+        text.as_str().into()
+    }
+}
+
+impl Borrow<str> for Str8 {
+    #[inline(always)]
+    fn borrow(&self) -> &str {
+        self.as_str()
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl FromStr for Str8 {
+    type Err = Infallible;
+
+    #[inline]
+    fn from_str(s: &str) -> Result<Str8, Self::Err> {
+        Ok(Str8::from(s))
+    }
+}
+
+/// A builder to incrementally construct a [`Str8`].
+///
+/// This is a [`Str16Builder`], which keeps the string in a buffer on the stack as long as it
+/// fits inline into a [`Str16`], and converts it into a [`Str8`] when it is finished.
+#[cfg(feature = "alloc")]
+#[derive(Clone, Debug, Default)]
+pub struct Str8Builder(Str16Builder);
+
+#[cfg(feature = "alloc")]
+impl Str8Builder {
+    /// Creates a new, empty builder.
+    #[inline]
+    pub const fn new() -> Self {
+        Self(Str16Builder::new())
+    }
+
+    /// Appends the given `char` to the string.
+    #[inline]
+    pub fn push(&mut self, ch: char) {
+        self.0.push(ch);
+    }
+
+    /// Appends the given string slice to the string.
+    #[inline]
+    pub fn push_str(&mut self, s: &str) {
+        self.0.push_str(s);
+    }
+
+    /// Appends the given string slice to the string, returning an error instead of
+    /// aborting if the string can not grow.
+    #[inline]
+    pub fn try_push_str(&mut self, s: &str) -> Result<(), TryReserveError> {
+        self.0.try_push_str(s)
+    }
+
+    /// Consumes the builder and returns the finished [`Str8`].
+    pub fn finish(self) -> Str8 {
+        self.0.finish_with(|text| Str8::new(text), Str8::new)
+    }
+
+    /// Consumes the builder and returns the finished [`Str8`], returning an error
+    /// instead of aborting if it can not be allocated on the heap.
+    pub fn try_finish(self) -> Result<Str8, TryReserveError> {
+        self.0
+            .finish_with(|text| Str8::try_new(text), Str8::try_new)
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl fmt::Write for Str8Builder {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.push_str(s);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "alloc")]
+impl From<Str8Builder> for Str8 {
+    #[inline]
+    fn from(builder: Str8Builder) -> Str8 {
+        builder.finish()
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Str8 {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> Result<Self, arbitrary::Error> {
+        let s = <&str>::arbitrary(u)?;
+        Str8::try_new(s).map_err(|_| arbitrary::Error::IncorrectFormat)
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde {
+    #[cfg(feature = "alloc")]
+    use alloc::{string::String, vec::Vec};
+    #[cfg(feature = "alloc")]
+    use core::fmt;
+
+    #[cfg(feature = "alloc")]
+    use serde::de::{Deserializer, Error, Unexpected, Visitor};
+
+    use crate::Str8;
+
+    #[cfg(feature = "alloc")]
+    // https://github.com/serde-rs/serde/blob/629802f2abfd1a54a6072992888fea7ca5bc209f/serde/src/private/de.rs#L56-L125
+    fn str8<'de: 'a, 'a, D>(deserializer: D) -> Result<Str8, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Str8Visitor;
+
+        impl<'a> Visitor<'a> for Str8Visitor {
+            type Value = Str8;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Str8::from(v))
+            }
+
+            fn visit_borrowed_str<E>(self, v: &'a str) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Str8::from(v))
+            }
+
+            fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                Ok(Str8::from(v))
+            }
+
+            fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => Ok(Str8::from(s)),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_borrowed_bytes<E>(self, v: &'a [u8]) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match core::str::from_utf8(v) {
+                    Ok(s) => Ok(Str8::from(s)),
+                    Err(_) => Err(Error::invalid_value(Unexpected::Bytes(v), &self)),
+                }
+            }
+
+            fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+            where
+                E: Error,
+            {
+                match String::from_utf8(v) {
+                    Ok(s) => Ok(Str8::from(s)),
+                    Err(e) => Err(Error::invalid_value(
+                        Unexpected::Bytes(&e.into_bytes()),
+                        &self,
+                    )),
+                }
+            }
+        }
+
+        deserializer.deserialize_str(Str8Visitor)
+    }
+
+    impl serde::Serialize for Str8 {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: serde::Serializer,
+        {
+            self.as_str().serialize(serializer)
+        }
+    }
+
+    #[cfg(feature = "alloc")]
+    impl<'de> serde::Deserialize<'de> for Str8 {
+        fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+        where
+            D: serde::Deserializer<'de>,
+        {
+            str8(deserializer)
+        }
+    }
+}
@@ -0,0 +1,223 @@
+use core::borrow::Borrow;
+use core::ops::Deref;
+use core::{fmt, hash};
+
+use crate::{Counter, PrefixStr16, Size, SmolBuf, SmolStr, SupportedSize};
+
+/// The common interface of the string types in this crate, to write code which is
+/// generic over all of them.
+///
+/// This is implemented for [`SmolStr`], including [`LocalSmolStr`](crate::LocalSmolStr),
+/// and [`PrefixStr16`].
+/// [`Str8`](crate::Str8) does not implement it, as its `new_static` takes a
+/// `&'static &'static str` instead of the `&'static str` taken by this trait.
+///
+/// The supertraits are the ones an interner like `Intern` needs
+/// to store the strings in a set, and look them up by `&str`.
+/// Interners hand out strings through `intern_with`.
+///
+/// This trait is sealed, so that methods which depend on the `alloc` feature
+/// can be required.
+pub trait SmolString:
+    sealed::Sealed
+    + Clone
+    + Default
+    + Eq
+    + Ord
+    + hash::Hash
+    + fmt::Debug
+    + fmt::Display
+    + Deref<Target = str>
+    + AsRef<str>
+    + Borrow<str>
+{
+    /// The number of bytes that are stored inline.
+    const INLINE_CAP: usize;
+
+    /// Constructs a string, allocating it on the heap if it does not fit inline.
+    #[cfg(feature = "alloc")]
+    fn new(text: &str) -> Self;
+
+    /// Constructs a string from a statically allocated string, which never allocates.
+    fn new_static(text: &'static str) -> Self;
+
+    /// Constructs an inline string.
+    ///
+    /// Panics if `text.len()` is larger than [`INLINE_CAP`](Self::INLINE_CAP).
+    fn new_inline(text: &str) -> Self;
+
+    fn as_str(&self) -> &str;
+
+    fn is_heap_allocated(&self) -> bool;
+
+    /// Returns the interned string for `text`, calling `lookup` to find or insert
+    /// the heap-allocated string shared by the interner.
+    ///
+    /// Strings which fit inline are constructed without calling `lookup`,
+    /// as sharing them would not save any memory.
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn intern_with(text: &str, lookup: impl FnOnce(&str) -> Self) -> Self {
+        if text.len() <= Self::INLINE_CAP {
+            return Self::new_inline(text);
+        }
+        lookup(text)
+    }
+}
+
+/// The common interface of the byte buffer types in this crate, to write code which is
+/// generic over all of them.
+///
+/// This is implemented for [`SmolBuf`], including [`LocalSmolBuf`](crate::LocalSmolBuf).
+///
+/// This trait is sealed, like [`SmolString`].
+pub trait SmolBytes:
+    sealed::Sealed
+    + Clone
+    + Default
+    + Eq
+    + Ord
+    + hash::Hash
+    + fmt::Debug
+    + Deref<Target = [u8]>
+    + AsRef<[u8]>
+    + Borrow<[u8]>
+{
+    /// The number of bytes that are stored inline.
+    const INLINE_CAP: usize;
+
+    /// Constructs a buffer, allocating it on the heap if it does not fit inline.
+    #[cfg(feature = "alloc")]
+    fn new(bytes: &[u8]) -> Self;
+
+    /// Constructs a buffer from a statically allocated slice, which never allocates.
+    fn new_static(bytes: &'static [u8]) -> Self;
+
+    /// Constructs an inline buffer.
+    ///
+    /// Panics if `bytes.len()` is larger than [`INLINE_CAP`](Self::INLINE_CAP).
+    fn new_inline(bytes: &[u8]) -> Self;
+
+    fn as_bytes(&self) -> &[u8];
+
+    fn is_heap_allocated(&self) -> bool;
+
+    /// Returns the interned buffer for `bytes`, calling `lookup` to find or insert
+    /// the heap-allocated buffer shared by the interner.
+    ///
+    /// See [`SmolString::intern_with`].
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn intern_with(bytes: &[u8], lookup: impl FnOnce(&[u8]) -> Self) -> Self {
+        if bytes.len() <= Self::INLINE_CAP {
+            return Self::new_inline(bytes);
+        }
+        lookup(bytes)
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+impl<const N: usize, C: Counter> sealed::Sealed for SmolStr<N, C> where Size<N>: SupportedSize {}
+
+impl<const N: usize, C: Counter> SmolString for SmolStr<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    const INLINE_CAP: usize = SmolBuf::<N>::INLINE_CAP;
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn new(text: &str) -> Self {
+        SmolStr::new(text)
+    }
+
+    #[inline]
+    fn new_static(text: &'static str) -> Self {
+        SmolStr::new_static(text)
+    }
+
+    #[inline]
+    fn new_inline(text: &str) -> Self {
+        SmolStr::new_inline(text)
+    }
+
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        SmolStr::as_str(self)
+    }
+
+    #[inline(always)]
+    fn is_heap_allocated(&self) -> bool {
+        SmolStr::is_heap_allocated(self)
+    }
+}
+
+impl sealed::Sealed for PrefixStr16 {}
+
+impl SmolString for PrefixStr16 {
+    const INLINE_CAP: usize = PrefixStr16::INLINE_CAP;
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn new(text: &str) -> Self {
+        PrefixStr16::new(text)
+    }
+
+    #[inline]
+    fn new_static(text: &'static str) -> Self {
+        PrefixStr16::new_static(text)
+    }
+
+    #[inline]
+    fn new_inline(text: &str) -> Self {
+        PrefixStr16::new_inline(text)
+    }
+
+    #[inline(always)]
+    fn as_str(&self) -> &str {
+        PrefixStr16::as_str(self)
+    }
+
+    #[inline(always)]
+    fn is_heap_allocated(&self) -> bool {
+        PrefixStr16::is_heap_allocated(self)
+    }
+}
+
+impl<const N: usize, C: Counter> sealed::Sealed for SmolBuf<N, C> where Size<N>: SupportedSize {}
+
+impl<const N: usize, C: Counter> SmolBytes for SmolBuf<N, C>
+where
+    Size<N>: SupportedSize,
+{
+    const INLINE_CAP: usize = SmolBuf::<N>::INLINE_CAP;
+
+    #[cfg(feature = "alloc")]
+    #[inline]
+    fn new(bytes: &[u8]) -> Self {
+        SmolBuf::new(bytes)
+    }
+
+    #[inline]
+    fn new_static(bytes: &'static [u8]) -> Self {
+        SmolBuf::new_static(bytes)
+    }
+
+    #[inline]
+    fn new_inline(bytes: &[u8]) -> Self {
+        SmolBuf::new_inline(bytes)
+    }
+
+    #[inline(always)]
+    fn as_bytes(&self) -> &[u8] {
+        SmolBuf::as_bytes(self)
+    }
+
+    #[inline(always)]
+    fn is_heap_allocated(&self) -> bool {
+        SmolBuf::is_heap_allocated(self)
+    }
+}
@@ -0,0 +1,301 @@
+#![cfg(feature = "alloc")]
+
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+
+use proptest::collection::vec;
+use proptest::{prop_assert, prop_assert_eq, proptest};
+
+use smol_buf::{Buf16, Repr};
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn buf16_is_smol() {
+    assert_eq!(
+        ::std::mem::size_of::<Buf16>(),
+        ::std::mem::size_of::<Arc<[u8]>>(),
+    );
+}
+
+#[test]
+fn assert_traits() {
+    fn f<T: Send + Sync + ::std::fmt::Debug + Clone + Ord + ::std::hash::Hash>() {}
+    f::<Buf16>();
+}
+
+#[test]
+fn conversions() {
+    let b: Buf16 = b"Hello, World!".as_slice().into();
+    let b: Vec<u8> = b.into();
+    assert_eq!(b, b"Hello, World!");
+
+    let b: Buf16 = Arc::<[u8]>::from(&b"Hello, World!"[..]).into();
+    assert!(!b.is_heap_allocated());
+    let b: Arc<[u8]> = b.into();
+    assert_eq!(b.as_ref(), b"Hello, World!");
+
+    let long = b"Hello, World! This does not fit inline.";
+    let arc = Arc::<[u8]>::from(&long[..]);
+    let b: Buf16 = arc.clone().into();
+    assert!(b.is_heap_allocated());
+    let b: Arc<[u8]> = b.into();
+    assert!(Arc::ptr_eq(&arc, &b));
+
+    let b: Buf16 = Cow::Borrowed(&long[..]).into();
+    assert_eq!(b, &long[..]);
+    let b: Buf16 = Box::<[u8]>::from(&long[..]).into();
+    assert_eq!(b, long.to_vec());
+}
+
+#[test]
+fn const_fn_ctor() {
+    const EMPTY: Buf16 = Buf16::new_inline(b"");
+    const A: Buf16 = Buf16::new_inline(b"A");
+    const LONG: Buf16 = Buf16::new_inline(b"ABCDEFGHIZKLMNO");
+
+    assert_eq!(EMPTY, Buf16::from(&b""[..]));
+    assert_eq!(A, Buf16::from(&b"A"[..]));
+    assert_eq!(LONG, Buf16::from(&b"ABCDEFGHIZKLMNO"[..]));
+}
+
+fn check_props(std_buf: &[u8], smol: Buf16) -> Result<(), proptest::test_runner::TestCaseError> {
+    prop_assert_eq!(smol.as_bytes(), std_buf);
+    prop_assert_eq!(smol.len(), std_buf.len());
+    prop_assert_eq!(smol.is_empty(), std_buf.is_empty());
+    if smol.len() <= 15 {
+        prop_assert!(!smol.is_heap_allocated());
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(b: Vec<u8>) {
+        check_props(&b, Buf16::new(&b))?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn from_byte_iter(b: Vec<u8>) {
+        check_props(&b, b.iter().copied().collect())?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ordering(a: Vec<u8>, b: Vec<u8>) {
+        prop_assert_eq!(Buf16::new(&a).cmp(&Buf16::new(&b)), a.cmp(&b));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ordering_with_zeros(a in vec(0..2u8, 0..40), b in vec(0..2u8, 0..40)) {
+        prop_assert_eq!(Buf16::new(&a).cmp(&Buf16::new(&b)), a.cmp(&b));
+        prop_assert_eq!(Buf16::new(&a) == Buf16::new(&b), a == b);
+    }
+}
+
+#[test]
+fn test_search_in_maps() {
+    let mut m = HashMap::<Buf16, i32>::new();
+    m.insert(b"aaa".as_slice().into(), 17);
+    assert_eq!(17, *m.get(b"aaa".as_slice()).unwrap());
+
+    let mut m = BTreeMap::<Buf16, i32>::new();
+    m.insert(b"aaa".as_slice().into(), 17);
+    assert_eq!(17, *m.get(b"aaa".as_slice()).unwrap());
+}
+
+#[test]
+fn test_debug() {
+    let b = Buf16::new(b"ab");
+    assert_eq!(format!("{b:?}"), "[97, 98]");
+}
+
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+    use serde::de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct SmolBufStruct {
+        pub(crate) b: Buf16,
+        pub(crate) vec: Vec<Buf16>,
+        pub(crate) map: HashMap<u32, Buf16>,
+    }
+
+    #[test]
+    fn test_serde() {
+        let b = Buf16::new(b"Hello");
+        let s = serde_json::to_string(&b).unwrap();
+        assert_eq!(s, "[72,101,108,108,111]");
+        let b: Buf16 = serde_json::from_str(&s).unwrap();
+        assert_eq!(b, b"Hello".as_slice());
+        assert!(!b.is_heap_allocated());
+    }
+
+    #[test]
+    fn test_serde_long() {
+        let long = b"Hello, World! This does not fit inline.";
+        let s = serde_json::to_string(&Buf16::new(long)).unwrap();
+        let b: Buf16 = serde_json::from_str(&s).unwrap();
+        assert_eq!(b, long.as_slice());
+        let b: Buf16 = serde_json::from_reader(std::io::Cursor::new(s)).unwrap();
+        assert_eq!(b, long.as_slice());
+    }
+
+    #[test]
+    fn test_serde_bytes() {
+        let long = b"Hello, World! This does not fit inline.";
+        let de = BytesDeserializer::<Error>::new(long);
+        let b = Buf16::deserialize(de).unwrap();
+        assert_eq!(b, long.as_slice());
+
+        let de = BorrowedBytesDeserializer::<Error>::new(b"Hello");
+        let b = Buf16::deserialize(de).unwrap();
+        assert_eq!(b, b"Hello".as_slice());
+        assert!(!b.is_heap_allocated());
+    }
+
+    #[test]
+    fn test_serde_struct() {
+        let mut map = HashMap::new();
+        map.insert(1, Buf16::new(b"ohno"));
+        let struct_ = SmolBufStruct {
+            b: Buf16::new(b"Hello, World"),
+            vec: vec![Buf16::new(b"Hello, World"), Buf16::new(b"Hello, World")],
+            map,
+        };
+        let s = serde_json::to_string(&struct_).unwrap();
+        let new_struct: SmolBufStruct = serde_json::from_str(&s).unwrap();
+        assert_eq!(new_struct.b, struct_.b);
+        assert_eq!(new_struct.vec, struct_.vec);
+        assert_eq!(new_struct.map, struct_.map);
+    }
+}
+
+#[test]
+fn test_slice() {
+    let buf = Buf16::new(&[7; 100]);
+    let sub = buf.slice(10..60);
+    assert_eq!(sub, &[7; 50][..]);
+    assert!(std::ptr::eq(sub.as_ptr(), buf[10..].as_ptr()));
+    drop(buf);
+
+    let sub2 = sub.clone().slice(..=40);
+    assert_eq!(sub2.len(), 41);
+    assert!(std::ptr::eq(sub2.as_ptr(), sub.as_ptr()));
+    drop(sub);
+    assert_eq!(sub2, &[7; 41][..]);
+    assert_eq!(sub2.slice(1..3), &[7, 7][..]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_slice_huge() {
+    // slicing a huge buffer falls back to copying
+    let huge = Buf16::new(&vec![1; 1 << 20]);
+    let sub = huge.slice(1..100);
+    assert_eq!(sub, &[1; 99][..]);
+    assert!(!std::ptr::eq(sub.as_ptr(), huge[1..].as_ptr()));
+}
+
+#[test]
+fn test_mutation() {
+    let mut b = Buf16::new(b"ab");
+    b.push(b'c');
+    b.insert_slice(1, b"-");
+    assert_eq!(b, b"a-bc".as_slice());
+    b.make_mut()[0] = b'A';
+    assert_eq!(b, b"A-bc".as_slice());
+    assert!(!b.is_heap_allocated());
+
+    b.extend_from_slice(&[b'x'; 15]);
+    assert!(b.is_heap_allocated());
+    assert_eq!(b.len(), 15 + 4);
+
+    let shared = b.clone();
+    b.make_mut()[0] = b'a';
+    assert_eq!(shared[0], b'A');
+    assert_eq!(b[0], b'a');
+
+    let ptr = b.as_ptr();
+    b.make_mut()[1] = b'_';
+    assert_eq!(b.as_ptr(), ptr);
+    b.truncate(15 + 1);
+    assert_eq!(b.as_ptr(), ptr);
+    assert_eq!(&b[..4], b"a_bc");
+    b.truncate(3);
+    assert_eq!(b, b"a_b".as_slice());
+
+    b.clear();
+    assert!(b.is_empty());
+}
+
+#[test]
+fn test_introspection() {
+    let inline = Buf16::new(b"smol");
+    assert_eq!(inline.kind(), Repr::Inline);
+    assert_eq!(inline.strong_count(), None);
+
+    let bytes: &'static [u8] = &[1; 64];
+    let b = Buf16::new_static(bytes);
+    assert_eq!(b.kind(), Repr::Static);
+    assert!(std::ptr::eq(b.as_static().unwrap(), bytes));
+
+    let heap = Buf16::new(bytes);
+    assert_eq!(heap.kind(), Repr::Heap);
+    assert_eq!(heap.strong_count(), Some(1));
+    assert!(heap.ptr_eq(&heap.clone()));
+    assert!(!heap.ptr_eq(&b));
+}
+
+#[test]
+fn test_try_new() {
+    let inline = Buf16::try_new(b"smol").unwrap();
+    assert_eq!(inline.kind(), Repr::Inline);
+
+    let bytes = [7; 64];
+    let mut heap = Buf16::try_new(&bytes).unwrap();
+    assert_eq!(heap, &bytes[..]);
+    assert_eq!(heap.kind(), Repr::Heap);
+    assert_eq!(heap.strong_count(), Some(1));
+
+    let clone = heap.clone();
+    assert!(heap.ptr_eq(&clone));
+    assert_eq!(heap.strong_count(), Some(2));
+
+    heap.make_mut()[0] = 1;
+    assert_eq!(heap[..2], [1, 7]);
+    assert_eq!(clone.strong_count(), Some(1));
+    assert_eq!(clone, &bytes[..]);
+
+    let sub = clone.slice(1..40);
+    assert_eq!(sub, &bytes[1..40]);
+    assert_eq!(Arc::<[u8]>::from(sub), Arc::from(&bytes[1..40]));
+}
+
+#[test]
+fn test_try_from_iter() {
+    let b = Buf16::try_from_iter(0..10).unwrap();
+    assert_eq!(b, &(0..10).collect::<Vec<u8>>());
+    assert_eq!(b.kind(), Repr::Inline);
+
+    let b = Buf16::try_from_iter(0..100).unwrap();
+    assert_eq!(b, &(0..100).collect::<Vec<u8>>());
+    assert_eq!(b.kind(), Repr::Heap);
+}
+
+#[test]
+fn const_new_static() {
+    const LONG: Buf16 = Buf16::new_static(&[7; 32]);
+    const SHORT: Buf16 = Buf16::new_static(b"smol");
+
+    assert_eq!(LONG.kind(), Repr::Static);
+    assert_eq!(LONG, [7; 32][..]);
+    assert_eq!(LONG.slice(1..20).kind(), Repr::Static);
+    assert_eq!(SHORT.kind(), Repr::Inline);
+    assert_eq!(SHORT, b"smol"[..]);
+}
@@ -0,0 +1,385 @@
+#![cfg(feature = "alloc")]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use proptest::collection::vec;
+use proptest::{prop_assert, prop_assert_eq, proptest};
+
+use smol_buf::{Buf16, Buf24, Repr, SmolAlloc};
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn buf24_is_smol() {
+    assert_eq!(
+        ::std::mem::size_of::<Buf24>(),
+        ::std::mem::size_of::<Vec<u8>>(),
+    );
+}
+
+#[test]
+fn assert_traits() {
+    fn f<T: Send + Sync + ::std::fmt::Debug + Clone + Ord + ::std::hash::Hash>() {}
+    f::<Buf24>();
+}
+
+#[test]
+fn conversions() {
+    let b: Buf24 = b"Hello, World!".as_slice().into();
+    let b: Vec<u8> = b.into();
+    assert_eq!(b, b"Hello, World!");
+
+    let b: Buf24 = Arc::<[u8]>::from(&b"Hello, World!"[..]).into();
+    assert!(!b.is_heap_allocated());
+    let b: Arc<[u8]> = b.into();
+    assert_eq!(b.as_ref(), b"Hello, World!");
+
+    let long = b"Hello, World! This does not fit inline.";
+    let arc = Arc::<[u8]>::from(&long[..]);
+    let b: Buf24 = arc.clone().into();
+    assert!(b.is_heap_allocated());
+    let b: Arc<[u8]> = b.into();
+    assert!(Arc::ptr_eq(&arc, &b));
+
+    let b: Buf24 = Cow::Borrowed(&long[..]).into();
+    assert_eq!(b, &long[..]);
+    let b: Buf24 = Box::<[u8]>::from(&long[..]).into();
+    assert_eq!(b, long.to_vec());
+}
+
+#[test]
+fn const_fn_ctor() {
+    const EMPTY: Buf24 = Buf24::new_inline(b"");
+    const A: Buf24 = Buf24::new_inline(b"A");
+    const LONG: Buf24 = Buf24::new_inline(b"ABCDEFGHIZKLMNOPQRSTUVW");
+
+    assert_eq!(EMPTY, Buf24::from(&b""[..]));
+    assert_eq!(A, Buf24::from(&b"A"[..]));
+    assert_eq!(LONG, Buf24::from(&b"ABCDEFGHIZKLMNOPQRSTUVW"[..]));
+}
+
+fn check_props(std_buf: &[u8], smol: Buf24) -> Result<(), proptest::test_runner::TestCaseError> {
+    prop_assert_eq!(smol.as_bytes(), std_buf);
+    prop_assert_eq!(smol.len(), std_buf.len());
+    prop_assert_eq!(smol.is_empty(), std_buf.is_empty());
+    if smol.len() <= 23 {
+        prop_assert!(!smol.is_heap_allocated());
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(b: Vec<u8>) {
+        check_props(&b, Buf24::new(&b))?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn from_byte_iter(b: Vec<u8>) {
+        check_props(&b, b.iter().copied().collect())?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ordering(a: Vec<u8>, b: Vec<u8>) {
+        prop_assert_eq!(Buf24::new(&a).cmp(&Buf24::new(&b)), a.cmp(&b));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ordering_with_zeros(a in vec(0..2u8, 0..40), b in vec(0..2u8, 0..40)) {
+        prop_assert_eq!(Buf24::new(&a).cmp(&Buf24::new(&b)), a.cmp(&b));
+        prop_assert_eq!(Buf24::new(&a) == Buf24::new(&b), a == b);
+    }
+}
+
+#[test]
+fn test_search_in_maps() {
+    let mut m = HashMap::<Buf24, i32>::new();
+    m.insert(b"aaa".as_slice().into(), 17);
+    assert_eq!(17, *m.get(b"aaa".as_slice()).unwrap());
+
+    let mut m = BTreeMap::<Buf24, i32>::new();
+    m.insert(b"aaa".as_slice().into(), 17);
+    assert_eq!(17, *m.get(b"aaa".as_slice()).unwrap());
+}
+
+#[test]
+fn test_debug() {
+    let b = Buf24::new(b"ab");
+    assert_eq!(format!("{b:?}"), "[97, 98]");
+}
+
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+    use serde::de::value::{BorrowedBytesDeserializer, BytesDeserializer, Error};
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize, Deserialize)]
+    struct SmolBufStruct {
+        pub(crate) b: Buf24,
+        pub(crate) vec: Vec<Buf24>,
+        pub(crate) map: HashMap<u32, Buf24>,
+    }
+
+    #[test]
+    fn test_serde() {
+        let b = Buf24::new(b"Hello");
+        let s = serde_json::to_string(&b).unwrap();
+        assert_eq!(s, "[72,101,108,108,111]");
+        let b: Buf24 = serde_json::from_str(&s).unwrap();
+        assert_eq!(b, b"Hello".as_slice());
+        assert!(!b.is_heap_allocated());
+    }
+
+    #[test]
+    fn test_serde_long() {
+        let long = b"Hello, World! This does not fit inline.";
+        let s = serde_json::to_string(&Buf24::new(long)).unwrap();
+        let b: Buf24 = serde_json::from_str(&s).unwrap();
+        assert_eq!(b, long.as_slice());
+        let b: Buf24 = serde_json::from_reader(std::io::Cursor::new(s)).unwrap();
+        assert_eq!(b, long.as_slice());
+    }
+
+    #[test]
+    fn test_serde_bytes() {
+        let long = b"Hello, World! This does not fit inline.";
+        let de = BytesDeserializer::<Error>::new(long);
+        let b = Buf24::deserialize(de).unwrap();
+        assert_eq!(b, long.as_slice());
+
+        let de = BorrowedBytesDeserializer::<Error>::new(b"Hello");
+        let b = Buf24::deserialize(de).unwrap();
+        assert_eq!(b, b"Hello".as_slice());
+        assert!(!b.is_heap_allocated());
+    }
+
+    #[test]
+    fn test_serde_struct() {
+        let mut map = HashMap::new();
+        map.insert(1, Buf24::new(b"ohno"));
+        let struct_ = SmolBufStruct {
+            b: Buf24::new(b"Hello, World"),
+            vec: vec![Buf24::new(b"Hello, World"), Buf24::new(b"Hello, World")],
+            map,
+        };
+        let s = serde_json::to_string(&struct_).unwrap();
+        let new_struct: SmolBufStruct = serde_json::from_str(&s).unwrap();
+        assert_eq!(new_struct.b, struct_.b);
+        assert_eq!(new_struct.vec, struct_.vec);
+        assert_eq!(new_struct.map, struct_.map);
+    }
+}
+
+#[test]
+fn test_slice() {
+    let buf = Buf24::new(&[7; 100]);
+    let sub = buf.slice(10..60);
+    assert_eq!(sub, &[7; 50][..]);
+    assert!(std::ptr::eq(sub.as_ptr(), buf[10..].as_ptr()));
+    drop(buf);
+
+    let sub2 = sub.clone().slice(..=40);
+    assert_eq!(sub2.len(), 41);
+    assert!(std::ptr::eq(sub2.as_ptr(), sub.as_ptr()));
+    drop(sub);
+    assert_eq!(sub2, &[7; 41][..]);
+    assert_eq!(sub2.slice(1..3), &[7, 7][..]);
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn test_slice_huge() {
+    // slicing a huge buffer falls back to copying
+    let huge = Buf24::new(&vec![1; 1 << 28]);
+    let sub = huge.slice(1..100);
+    assert_eq!(sub, &[1; 99][..]);
+    assert!(!std::ptr::eq(sub.as_ptr(), huge[1..].as_ptr()));
+}
+
+#[test]
+fn test_mutation() {
+    let mut b = Buf24::new(b"ab");
+    b.push(b'c');
+    b.insert_slice(1, b"-");
+    assert_eq!(b, b"a-bc".as_slice());
+    b.make_mut()[0] = b'A';
+    assert_eq!(b, b"A-bc".as_slice());
+    assert!(!b.is_heap_allocated());
+
+    b.extend_from_slice(&[b'x'; 23]);
+    assert!(b.is_heap_allocated());
+    assert_eq!(b.len(), 23 + 4);
+
+    let shared = b.clone();
+    b.make_mut()[0] = b'a';
+    assert_eq!(shared[0], b'A');
+    assert_eq!(b[0], b'a');
+
+    let ptr = b.as_ptr();
+    b.make_mut()[1] = b'_';
+    assert_eq!(b.as_ptr(), ptr);
+    b.truncate(23 + 1);
+    assert_eq!(b.as_ptr(), ptr);
+    assert_eq!(&b[..4], b"a_bc");
+    b.truncate(3);
+    assert_eq!(b, b"a_b".as_slice());
+
+    b.clear();
+    assert!(b.is_empty());
+}
+
+#[test]
+fn test_introspection() {
+    let inline = Buf24::new(b"smol");
+    assert_eq!(inline.kind(), Repr::Inline);
+    assert_eq!(inline.strong_count(), None);
+
+    let bytes: &'static [u8] = &[1; 64];
+    let b = Buf24::new_static(bytes);
+    assert_eq!(b.kind(), Repr::Static);
+    assert!(std::ptr::eq(b.as_static().unwrap(), bytes));
+
+    let heap = Buf24::new(bytes);
+    assert_eq!(heap.kind(), Repr::Heap);
+    assert_eq!(heap.strong_count(), Some(1));
+    assert!(heap.ptr_eq(&heap.clone()));
+    assert!(!heap.ptr_eq(&b));
+}
+
+#[test]
+fn test_try_new() {
+    let inline = Buf24::try_new(b"smol").unwrap();
+    assert_eq!(inline.kind(), Repr::Inline);
+
+    let bytes = [7; 64];
+    let mut heap = Buf24::try_new(&bytes).unwrap();
+    assert_eq!(heap, &bytes[..]);
+    assert_eq!(heap.kind(), Repr::Heap);
+    assert_eq!(heap.strong_count(), Some(1));
+
+    let clone = heap.clone();
+    assert!(heap.ptr_eq(&clone));
+    assert_eq!(heap.strong_count(), Some(2));
+
+    heap.make_mut()[0] = 1;
+    assert_eq!(heap[..2], [1, 7]);
+    assert_eq!(clone.strong_count(), Some(1));
+    assert_eq!(clone, &bytes[..]);
+
+    let sub = clone.slice(1..40);
+    assert_eq!(sub, &bytes[1..40]);
+    assert_eq!(Arc::<[u8]>::from(sub), Arc::from(&bytes[1..40]));
+}
+
+#[test]
+fn test_try_from_iter() {
+    let b = Buf24::try_from_iter(0..10).unwrap();
+    assert_eq!(b, &(0..10).collect::<Vec<u8>>());
+    assert_eq!(b.kind(), Repr::Inline);
+
+    let b = Buf24::try_from_iter(0..100).unwrap();
+    assert_eq!(b, &(0..100).collect::<Vec<u8>>());
+    assert_eq!(b.kind(), Repr::Heap);
+}
+
+#[derive(Default)]
+struct CountingAlloc {
+    live: AtomicUsize,
+}
+
+unsafe impl SmolAlloc for CountingAlloc {
+    fn allocate(&self, layout: Layout) -> *mut u8 {
+        self.live.fetch_add(1, Ordering::Relaxed);
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn deallocate(&self, ptr: *mut u8, layout: Layout) {
+        self.live.fetch_sub(1, Ordering::Relaxed);
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[test]
+fn test_new_in() {
+    let alloc = Arc::new(CountingAlloc::default());
+
+    let inline = Buf24::new_in(b"smol", &alloc);
+    assert_eq!(inline.kind(), Repr::Inline);
+    assert_eq!(alloc.live.load(Ordering::Relaxed), 0);
+
+    let bytes = [7; 64];
+    let heap = Buf24::new_in(&bytes, &alloc);
+    assert_eq!(heap, &bytes[..]);
+    assert_eq!(heap.kind(), Repr::Heap);
+    assert_eq!(alloc.live.load(Ordering::Relaxed), 1);
+    assert_eq!(Arc::strong_count(&alloc), 2);
+
+    let clone = heap.clone();
+    drop(heap);
+    assert_eq!(alloc.live.load(Ordering::Relaxed), 1);
+
+    let moved = smol_buf::Buf16::try_from(clone).unwrap();
+    assert_eq!(moved, &bytes[..]);
+    drop(moved);
+    assert_eq!(alloc.live.load(Ordering::Relaxed), 0);
+    assert_eq!(Arc::strong_count(&alloc), 1);
+}
+
+#[test]
+fn from_owner() {
+    struct Owner {
+        _alive: Arc<()>,
+        bytes: [u8; 64],
+    }
+
+    impl AsRef<[u8]> for Owner {
+        fn as_ref(&self) -> &[u8] {
+            &self.bytes
+        }
+    }
+
+    let alive = Arc::new(());
+    let mut bytes = [0; 64];
+    bytes.iter_mut().enumerate().for_each(|(i, b)| *b = i as u8);
+
+    let b = Buf24::from_owner(Owner {
+        _alive: alive.clone(),
+        bytes,
+    });
+    assert_eq!(b, bytes[..]);
+    assert_eq!(b.kind(), Repr::Heap);
+    assert_eq!(b.strong_count(), Some(1));
+    assert_eq!(Arc::strong_count(&alive), 2);
+
+    let slice = b.slice(8..40);
+    assert_eq!(slice, bytes[8..40]);
+    assert!(std::ptr::eq(slice.as_ptr(), b[8..].as_ptr()));
+    assert_eq!(b.strong_count(), Some(2));
+    assert_eq!(slice.slice(4..), bytes[12..40]);
+    assert_eq!(slice.slice(..4).kind(), Repr::Inline);
+
+    let b16 = Buf16::try_from(slice.clone()).unwrap();
+    assert!(std::ptr::eq(b16.as_ptr(), slice.as_ptr()));
+    assert_eq!(b16.strong_count(), Some(3));
+
+    let mut copy = slice.clone();
+    copy.make_mut()[0] = 0xff;
+    assert_eq!(slice[0], 8);
+    assert_eq!(copy.strong_count(), Some(1));
+
+    drop((b, slice, b16));
+    assert_eq!(Arc::strong_count(&alive), 1);
+
+    assert_eq!(Buf24::from_owner(vec![1, 2, 3]).kind(), Repr::Inline);
+    let vec = vec![1; 100];
+    let ptr = vec.as_ptr();
+    assert!(std::ptr::eq(Buf24::from_owner(vec).as_ptr(), ptr));
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "alloc")]
+
+use proptest::{prop_assert_eq, proptest};
+
+use smol_buf::{Buf32, Repr};
+
+#[test]
+fn buf32_is_smol() {
+    assert_eq!(::std::mem::size_of::<Buf32>(), 32);
+    assert_eq!(::std::mem::size_of::<Option<Buf32>>(), 32);
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(b: Vec<u8>) {
+        let smol = Buf32::new(&b);
+        prop_assert_eq!(smol.as_bytes(), &b[..]);
+        prop_assert_eq!(smol.is_heap_allocated(), b.len() > 31);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn from_byte_iter(b: Vec<u8>) {
+        prop_assert_eq!(b.iter().copied().collect::<Buf32>(), &b[..]);
+    }
+}
+
+#[test]
+fn test_mutation() {
+    let mut b = Buf32::new(&[1; 31]);
+    assert_eq!(b.kind(), Repr::Inline);
+    b.push(2);
+    assert_eq!(b.kind(), Repr::Heap);
+    assert_eq!(b.len(), 32);
+
+    let shared = b.clone();
+    b.make_mut()[0] = 0;
+    assert_eq!(shared[0], 1);
+    assert_eq!(b[0], 0);
+
+    b.truncate(31);
+    assert_eq!(b.kind(), Repr::Inline);
+}
+
+#[test]
+fn test_slice_huge() {
+    #[cfg(not(miri))]
+    let len = 1 << 20;
+    #[cfg(miri)]
+    let len = 1 << 10;
+    let huge = Buf32::new(&vec![1; len]);
+    let sub = huge.slice(1..100);
+    assert_eq!(sub, &[1; 99][..]);
+    assert!(std::ptr::eq(sub.as_ptr(), huge[1..].as_ptr()));
+    assert_eq!(sub.strong_count(), Some(2));
+}
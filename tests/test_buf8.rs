@@ -0,0 +1,68 @@
+#![cfg(feature = "alloc")]
+
+use proptest::{prop_assert_eq, proptest};
+
+use smol_buf::{Buf16, Buf24, Buf8, Repr};
+
+#[test]
+fn buf8_is_smol() {
+    assert_eq!(::std::mem::size_of::<Buf8>(), 8);
+    assert_eq!(::std::mem::size_of::<Option<Buf8>>(), 8);
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(b: Vec<u8>) {
+        let smol = Buf8::new(&b);
+        prop_assert_eq!(smol.as_bytes(), &b[..]);
+        prop_assert_eq!(smol.len(), b.len());
+        prop_assert_eq!(smol.is_heap_allocated(), b.len() > 7);
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn from_byte_iter(b: Vec<u8>) {
+        prop_assert_eq!(b.iter().copied().collect::<Buf8>(), &b[..]);
+        prop_assert_eq!(Buf8::try_from_iter(b.iter().copied()).unwrap(), &b[..]);
+    }
+}
+
+#[test]
+fn test_introspection() {
+    let inline = Buf8::new(b"smol");
+    assert_eq!(inline.kind(), Repr::Inline);
+    assert_eq!(inline.strong_count(), None);
+
+    let heap = Buf8::try_new(&[1; 64]).unwrap();
+    assert_eq!(heap, &[1; 64][..]);
+    assert_eq!(heap.kind(), Repr::Heap);
+    assert_eq!(heap.strong_count(), Some(1));
+    let clone = heap.clone();
+    assert!(heap.ptr_eq(&clone));
+    assert_eq!(heap.strong_count(), Some(2));
+    drop(clone);
+
+    let b16 = Buf16::from(heap);
+    assert_eq!(b16, &[1; 64][..]);
+    assert_eq!(b16.strong_count(), Some(1));
+    let heap = Buf8::from(b16);
+    assert_eq!(heap.len(), 64);
+}
+
+#[test]
+fn widened_to_inline() {
+    let mut b16: Buf16 = Buf8::new(b"abcdefghij").into();
+    assert_eq!(b16.kind(), Repr::Inline);
+    b16.push(b'Z');
+    assert_eq!(b16, &b"abcdefghijZ"[..]);
+
+    let mut b24: Buf24 = Buf8::new(b"abcdefghijklmnopqrstuvw").into();
+    assert_eq!(b24.kind(), Repr::Inline);
+    b24.push(b'Z');
+    assert_eq!(b24, &b"abcdefghijklmnopqrstuvwZ"[..]);
+
+    let b24: Buf24 = Buf8::new(b"abcdefghijklmnopqrstuvwxyz").into();
+    assert_eq!(b24.kind(), Repr::Heap);
+    assert_eq!(b24.strong_count(), Some(1));
+}
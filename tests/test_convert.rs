@@ -0,0 +1,174 @@
+#![cfg(feature = "alloc")]
+
+use smol_buf::{Buf16, Buf24, Repr, Size, SmolStr, Str16, Str24, Str32, SupportedSize};
+
+#[test]
+fn str16_to_str24() {
+    let s16 = Str16::new("smol");
+    let s24 = Str24::from(s16);
+    assert_eq!(s24, "smol");
+    assert_eq!(s24.kind(), Repr::Inline);
+
+    // heap strings which fit inline are re-inlined
+    let s16 = Str16::new("not so smol anymore");
+    let s24 = Str24::from(s16);
+    assert_eq!(s24, "not so smol anymore");
+    assert_eq!(s24.kind(), Repr::Inline);
+
+    let s16 = Str16::new("some text that is not so smol anymore");
+    let clone = s16.clone();
+    let s24 = Str24::from(s16);
+    assert_eq!(s24, "some text that is not so smol anymore");
+    assert!(std::ptr::eq(s24.as_ptr(), clone.as_ptr()));
+    assert_eq!(s24.strong_count(), Some(2));
+
+    let slice = Str24::from(clone.slice(1..));
+    assert!(std::ptr::eq(slice.as_ptr(), s24[1..].as_ptr()));
+    assert_eq!(slice.strong_count(), Some(3));
+
+    let text = "a static string which is not inline";
+    let s24 = Str24::from(Str16::new_static(text));
+    assert!(std::ptr::eq(s24.as_static().unwrap(), text));
+}
+
+#[test]
+fn str24_to_str16() {
+    let s16 = Str16::try_from(Str24::new("smol")).unwrap();
+    assert_eq!(s16, "smol");
+    assert_eq!(s16.kind(), Repr::Inline);
+
+    // inline strings which are too long are moved to the heap
+    let s16 = Str16::try_from(Str24::new("not so smol anymore")).unwrap();
+    assert_eq!(s16, "not so smol anymore");
+    assert_eq!(s16.kind(), Repr::Heap);
+
+    let s24 = Str24::new("some text that is not so smol anymore");
+    let s16 = Str16::try_from(s24.clone()).unwrap();
+    assert!(std::ptr::eq(s24.as_ptr(), s16.as_ptr()));
+
+    let slice = Str16::try_from(s24.slice(1..)).unwrap();
+    assert!(std::ptr::eq(slice.as_ptr(), s24[1..].as_ptr()));
+    assert_eq!(s24.strong_count(), Some(3));
+
+    let text = "a static string which is not inline";
+    let s16 = Str16::try_from(Str24::new_static(text)).unwrap();
+    assert!(std::ptr::eq(s16.as_static().unwrap(), text));
+}
+
+#[test]
+#[cfg_attr(miri, ignore)]
+fn buf24_slice_to_buf16() {
+    // the slice encoding of `Buf16` is more limited, so this copies
+    let parent = Buf24::new(&vec![1; 1 << 21]);
+    let slice = parent.slice(1..100);
+    let b16 = Buf16::try_from(slice.clone()).unwrap();
+    assert_eq!(b16, slice.as_bytes());
+    assert!(!b16.ptr_eq(&Buf16::try_from(slice.clone()).unwrap()));
+
+    let b24 = Buf24::from(b16);
+    assert_eq!(b24, slice);
+}
+
+#[test]
+fn buf_to_str() {
+    let bytes = Buf24::new(b"some bytes that are not so smol anymore");
+    let s = Str24::from_utf8(bytes.clone()).unwrap();
+    assert_eq!(s, "some bytes that are not so smol anymore");
+    assert!(s.as_buf().ptr_eq(&bytes));
+
+    let b = s.into_bytes();
+    assert!(b.ptr_eq(&bytes));
+
+    let s = Str16::from_utf8(Buf16::new(b"smol")).unwrap();
+    assert_eq!(s, "smol");
+    assert_eq!(s.as_buf(), b"smol".as_slice());
+
+    let s = unsafe { Str16::from_utf8_unchecked(Buf16::new_static(b"static bytes, not inline")) };
+    assert_eq!(s.as_static(), Some("static bytes, not inline"));
+}
+
+#[test]
+fn buf_to_str_invalid() {
+    let bytes = Buf16::new(b"invalid \xff utf-8 that is not so smol");
+    let err = Str16::from_utf8(bytes.clone()).unwrap_err();
+    assert_eq!(err.utf8_error().valid_up_to(), 8);
+    assert_eq!(err.as_bytes(), bytes.as_bytes());
+    assert_eq!(
+        err.to_string(),
+        "invalid utf-8 sequence of 1 bytes from index 8"
+    );
+    let _: &dyn std::error::Error = &err;
+    assert!(err.into_bytes().ptr_eq(&bytes));
+}
+
+#[test]
+fn try_new_conversions() {
+    let text = "a string which is too long to be stored inline";
+    let s16 = Str16::try_new(text).unwrap();
+    let s24 = Str24::from(s16.clone());
+    assert_eq!(s24, text);
+    assert!(s24.as_ptr() == s16.as_ptr());
+    assert_eq!(s24.strong_count(), Some(2));
+
+    drop(s16);
+    let s16 = Str16::try_from(s24).unwrap();
+    assert_eq!(s16, text);
+    assert_eq!(s16.strong_count(), Some(1));
+}
+
+#[test]
+fn try_new_length_in_header() {
+    let bytes: Vec<u8> = (0..=255).collect();
+    let b16 = Buf16::try_new(&bytes).unwrap();
+    assert_eq!(b16.len(), 256);
+    assert_eq!(b16.slice(250..).len(), 6);
+
+    let b24 = Buf24::from(b16.clone());
+    assert_eq!(b24.len(), 256);
+    assert_eq!(b24, &bytes[..]);
+
+    let b16 = Buf16::try_from(b24).unwrap();
+    assert_eq!(b16.len(), 256);
+    assert_eq!(b16, &bytes[..]);
+}
+
+#[test]
+fn try_new_widened_to_inline() {
+    let text = "0123456789abcdefghij";
+    let mut s: Str32 = Str16::try_new(text).unwrap().into();
+    assert_eq!(s.kind(), Repr::Inline);
+    s.push('!');
+    assert_eq!(s, "0123456789abcdefghij!");
+
+    let mut b: Buf24 = Buf16::try_new(text.as_bytes()).unwrap().into();
+    assert_eq!(b.kind(), Repr::Inline);
+    b.push(b'!');
+    assert_eq!(b, b"0123456789abcdefghij!"[..]);
+}
+
+fn shout<const N: usize>(text: &str) -> SmolStr<N>
+where
+    Size<N>: SupportedSize,
+{
+    let mut s = SmolStr::<N>::new(text);
+    s.make_mut().make_ascii_uppercase();
+    s
+}
+
+#[test]
+fn generic_over_size() {
+    assert_eq!(Str16::INLINE_CAP, 15);
+    assert_eq!(Str24::INLINE_CAP, 23);
+    assert_eq!(Str32::INLINE_CAP, 31);
+
+    let text = "twenty-seven bytes of text";
+    assert_eq!(shout::<16>(text).kind(), Repr::Heap);
+    assert_eq!(shout::<24>(text).kind(), Repr::Heap);
+    assert_eq!(shout::<32>(text).kind(), Repr::Inline);
+    assert_eq!(shout::<32>(text), "TWENTY-SEVEN BYTES OF TEXT");
+
+    let s32 = Str32::new("a heap string longer than thirty-one bytes");
+    let s16: Str16 = s32.clone().try_convert().unwrap();
+    assert!(s16.ptr_eq(&Str16::try_from(s32.clone()).unwrap()));
+    assert_eq!(s16.strong_count(), Some(2));
+}
@@ -0,0 +1,104 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use smol_buf::{BuildCachedHasher, HashedStr24, Repr, Str24, Str8};
+
+const TEXT: &str = "some text that is too long to be stored inline";
+
+#[test]
+fn test_cached_hash() {
+    let s = HashedStr24::new(TEXT);
+    assert_eq!(s, TEXT);
+    assert_eq!(s.as_str24().kind(), Repr::Heap);
+
+    let clone = s.clone();
+    let hash = s.cached_hash();
+    assert_eq!(clone.cached_hash(), hash);
+    assert_eq!(HashedStr24::new(TEXT).cached_hash(), hash);
+    assert_eq!(HashedStr24::new_static(TEXT).cached_hash(), hash);
+    assert_ne!(HashedStr24::new(&TEXT[1..]).cached_hash(), hash);
+
+    let build = BuildCachedHasher::default();
+    assert_eq!(build.hash_one(&s), build.hash_one(&clone));
+
+    let inline = HashedStr24::new("smol");
+    assert_eq!(
+        inline.cached_hash(),
+        HashedStr24::new_inline("smol").cached_hash()
+    );
+}
+
+#[test]
+fn test_eq() {
+    let a = HashedStr24::new(TEXT);
+    let b = HashedStr24::new(TEXT);
+    let c = HashedStr24::new(TEXT.to_uppercase());
+    assert_eq!(a, b);
+    assert_ne!(a, c);
+
+    a.cached_hash();
+    c.cached_hash();
+    assert_ne!(a, c);
+    assert_eq!(a, b);
+    b.cached_hash();
+    assert_eq!(a, b);
+}
+
+#[test]
+fn test_map() {
+    let mut map: HashMap<HashedStr24, usize, BuildCachedHasher> = HashMap::default();
+    for i in 0..100 {
+        map.insert(HashedStr24::new(format!("{TEXT} {i}")), i);
+    }
+    for i in 0..100 {
+        assert_eq!(map[&HashedStr24::new(format!("{TEXT} {i}"))], i);
+    }
+    assert!(!map.contains_key(&HashedStr24::new(TEXT)));
+}
+
+#[test]
+fn test_conversions() {
+    let s = Str24::try_new(TEXT).unwrap();
+    let hashed = HashedStr24::from(s.clone());
+    assert_eq!(hashed, TEXT);
+    assert!(!hashed.as_str24().ptr_eq(&s));
+
+    let s = Str24::new(TEXT);
+    let hashed = HashedStr24::from(s.clone());
+    assert_eq!(hashed, TEXT);
+    assert!(!hashed.as_str24().ptr_eq(&s));
+
+    let s = Str24::from(hashed.clone());
+    assert!(s.ptr_eq(hashed.as_str24()));
+    assert!(HashedStr24::from(s.clone()).as_str24().ptr_eq(&s));
+}
+
+#[test]
+fn test_make_mut_forgets_hash() {
+    let hashed = HashedStr24::new(TEXT);
+    hashed.cached_hash();
+    let mut s = Str24::from(hashed);
+    s.make_mut().make_ascii_uppercase();
+    let hashed = HashedStr24::from(s);
+    assert_eq!(hashed, TEXT.to_uppercase().as_str());
+    assert_eq!(
+        hashed.cached_hash(),
+        HashedStr24::new(TEXT.to_uppercase()).cached_hash()
+    );
+    assert_eq!(hashed, HashedStr24::new(TEXT.to_uppercase()));
+}
+
+#[test]
+fn test_str8_make_mut_forgets_hash() {
+    let hashed = HashedStr24::new(TEXT);
+    hashed.cached_hash();
+    let mut s = Str8::from(Str24::from(hashed));
+    s.make_mut().make_ascii_uppercase();
+    let hashed = HashedStr24::from(Str24::from(s));
+    let expected = HashedStr24::new(TEXT.to_uppercase());
+    expected.cached_hash();
+    assert_eq!(hashed.cached_hash(), expected.cached_hash());
+    assert_eq!(hashed, expected);
+}
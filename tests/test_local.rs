@@ -0,0 +1,203 @@
+#![cfg(feature = "alloc")]
+
+use std::rc::Rc;
+
+use smol_buf::{
+    Buf16, Buf24, Buf32, LocalBuf16, LocalBuf24, LocalBuf32, LocalStr16, LocalStr24,
+    LocalStr24Builder, LocalStr32, LocalStr32Builder, Repr, SmolBytes, SmolString, Str16, Str24,
+    Str32,
+};
+
+const TEXT: &str = "some text that is too long to be stored inline";
+
+#[test]
+#[cfg(target_pointer_width = "64")]
+fn local_is_smol() {
+    assert_eq!(std::mem::size_of::<LocalStr16>(), 16);
+    assert_eq!(std::mem::size_of::<Option<LocalStr16>>(), 16);
+    assert_eq!(std::mem::size_of::<LocalStr24>(), 24);
+    assert_eq!(std::mem::size_of::<Option<LocalStr24>>(), 24);
+    assert_eq!(std::mem::size_of::<LocalStr32>(), 32);
+    assert_eq!(std::mem::size_of::<Option<LocalStr32>>(), 32);
+}
+
+#[test]
+fn const_new_static() {
+    const LONG: LocalStr24 = LocalStr24::new_static(TEXT);
+    const SHORT: LocalBuf16 = LocalBuf16::new_static(b"smol");
+    assert_eq!(LONG.as_static(), Some(TEXT));
+    assert_eq!(LONG.kind(), Repr::Static);
+    assert_eq!(SHORT.kind(), Repr::Inline);
+    assert_eq!(SHORT, &b"smol"[..]);
+}
+
+#[test]
+fn local_str24() {
+    let s = LocalStr24::new(TEXT);
+    assert_eq!(s, TEXT);
+    assert_eq!(s.kind(), Repr::Heap);
+
+    let clone = s.clone();
+    assert!(s.ptr_eq(&clone));
+    assert_eq!(s.strong_count(), Some(2));
+
+    let sub = s.slice(5..40);
+    assert_eq!(sub, &TEXT[5..40]);
+    assert_eq!(s.strong_count(), Some(3));
+    drop((clone, sub));
+    assert_eq!(s.strong_count(), Some(1));
+
+    let rc: Rc<str> = s.into();
+    assert_eq!(&*rc, TEXT);
+    let s = LocalStr24::from(rc.clone());
+    assert_eq!(Rc::strong_count(&rc), 2);
+    assert_eq!(s, TEXT);
+
+    let s: LocalStr24 = TEXT.chars().collect();
+    assert_eq!(s, TEXT);
+
+    let mut builder = LocalStr24Builder::new();
+    builder.push_str("smol");
+    builder.push_str(TEXT);
+    assert_eq!(builder.finish(), format!("smol{TEXT}"));
+}
+
+#[test]
+fn local_str16() {
+    let mut s = LocalStr16::new("smol");
+    assert_eq!(s.kind(), Repr::Inline);
+    s.push_str(" and then some more text");
+    assert_eq!(s, "smol and then some more text");
+    assert_eq!(s.kind(), Repr::Heap);
+
+    let s24 = LocalStr24::from(s.clone());
+    assert!(s24.ptr_eq(&LocalStr24::from(s)));
+    assert_eq!(s24.strong_count(), Some(1));
+}
+
+#[test]
+fn local_str32() {
+    let mut s = LocalStr32::new("thirty-one bytes of inline text");
+    assert_eq!(s.kind(), Repr::Inline);
+    s.push('!');
+    assert_eq!(s.kind(), Repr::Heap);
+    assert_eq!(s.slice(..31), "thirty-one bytes of inline text");
+
+    let mut builder = LocalStr32Builder::new();
+    builder.push_str(TEXT);
+    let s = builder.finish();
+    assert_eq!(s.slice(8..).strong_count(), Some(2));
+
+    let s16 = LocalStr16::try_from(s.clone()).unwrap();
+    assert_eq!(s16.as_ptr(), s.as_ptr());
+    assert_eq!(s.strong_count(), Some(2));
+    assert_eq!(LocalStr32::from(s16), TEXT);
+    assert_eq!(Str32::from(s.clone()), TEXT);
+    assert_eq!(LocalStr32::from(Str32::new(TEXT)), s);
+
+    let b = LocalBuf32::try_new(TEXT.as_bytes()).unwrap();
+    let shared = Buf32::from(b.clone());
+    assert_eq!(shared.as_ptr(), b.as_ptr());
+    assert_eq!(LocalBuf24::try_from(b).unwrap(), TEXT.as_bytes());
+}
+
+#[test]
+fn convert_shared() {
+    let s24 = Str24::new(TEXT);
+    let local = LocalStr24::from(s24.clone());
+    assert_eq!(local, TEXT);
+    assert_eq!(local.kind(), Repr::Heap);
+    assert_eq!(s24.strong_count(), Some(1));
+    assert_eq!(Str24::from(local), TEXT);
+
+    let local = LocalStr16::from(Str16::new_static(TEXT));
+    assert_eq!(local.as_static(), Some(TEXT));
+    assert_eq!(Str16::from(local).as_static(), Some(TEXT));
+
+    let local = LocalBuf24::from(Buf24::new(b"smol"));
+    assert_eq!(local.kind(), Repr::Inline);
+    assert_eq!(Buf24::from(local), b"smol"[..]);
+}
+
+#[test]
+fn convert_try_new() {
+    let b16 = Buf16::try_new(TEXT.as_bytes()).unwrap();
+    let local = LocalBuf16::from(b16.clone());
+    assert_eq!(local.as_ptr(), b16.as_ptr());
+    assert_eq!(local.strong_count(), Some(2));
+    drop(b16);
+
+    let shared = Buf16::from(local.clone());
+    assert_eq!(shared, TEXT.as_bytes());
+    assert_eq!(shared.strong_count(), Some(2));
+}
+
+#[test]
+fn convert_owner() {
+    let local = LocalStr24::from_owner(TEXT.to_string());
+    assert_eq!(local.slice(5..).strong_count(), Some(2));
+
+    let shared = Str24::from(local.clone());
+    assert_eq!(shared.as_ptr(), local.as_ptr());
+    assert_eq!(shared.strong_count(), Some(2));
+}
+
+#[test]
+fn widened_to_inline() {
+    let b16 = LocalBuf16::try_new(b"sixteen or more bytes").unwrap();
+    assert_eq!(b16.kind(), Repr::Heap);
+    let mut b24 = LocalBuf24::from(b16);
+    assert_eq!(b24.kind(), Repr::Inline);
+    b24.push(b'!');
+    assert_eq!(b24, &b"sixteen or more bytes!"[..]);
+}
+
+fn roundtrip_str<S: SmolString>(text: &str) -> (S, bool) {
+    let s = S::new(text);
+    assert_eq!(s.as_str(), text);
+    assert_eq!(S::new_static(TEXT).as_str(), TEXT);
+    let inline = text.len() <= S::INLINE_CAP;
+    assert_eq!(s.is_heap_allocated(), !inline);
+
+    let mut looked_up = false;
+    let interned = S::intern_with(text, |text| {
+        looked_up = true;
+        S::new(text)
+    });
+    assert_eq!(interned.as_str(), text);
+    assert_eq!(looked_up, !inline);
+    (s, inline)
+}
+
+fn roundtrip_bytes<B: SmolBytes>(bytes: &[u8]) -> bool {
+    let b = B::new(bytes);
+    assert_eq!(b.as_bytes(), bytes);
+    assert_eq!(B::new_static(TEXT.as_bytes()).as_bytes(), TEXT.as_bytes());
+
+    let mut looked_up = false;
+    let interned = B::intern_with(bytes, |bytes| {
+        looked_up = true;
+        B::new(bytes)
+    });
+    assert_eq!(interned.as_bytes(), bytes);
+    assert_eq!(looked_up, b.is_heap_allocated());
+    !b.is_heap_allocated()
+}
+
+#[test]
+fn generic_traits() {
+    let text = "nineteen bytes long";
+    assert!(!roundtrip_str::<Str16>(text).1);
+    assert!(roundtrip_str::<Str24>(text).1);
+    assert!(roundtrip_str::<Str32>(text).1);
+    assert!(!roundtrip_str::<LocalStr16>(text).1);
+    assert!(roundtrip_str::<LocalStr24>(text).1);
+    assert!(roundtrip_str::<LocalStr32>(text).1);
+    assert_eq!(roundtrip_str::<LocalStr24>(TEXT).0, TEXT);
+
+    assert!(!roundtrip_bytes::<Buf16>(text.as_bytes()));
+    assert!(roundtrip_bytes::<Buf24>(text.as_bytes()));
+    assert!(!roundtrip_bytes::<LocalBuf16>(text.as_bytes()));
+    assert!(roundtrip_bytes::<LocalBuf24>(text.as_bytes()));
+    assert!(roundtrip_bytes::<LocalBuf32>(text.as_bytes()));
+}
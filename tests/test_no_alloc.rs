@@ -0,0 +1,63 @@
+#![cfg(not(feature = "alloc"))]
+
+use smol_buf::{Buf16, Buf24, Repr, Str16, Str24, Str8};
+
+const LONG: &str = "this string is too long to be stored inline";
+
+#[test]
+fn inline_and_static() {
+    let s = Str24::try_new("smol").unwrap();
+    assert_eq!(s, "smol");
+    assert_eq!(s.kind(), Repr::Inline);
+    assert_eq!(s.strong_count(), None);
+
+    let s = Str16::new_static(LONG);
+    assert_eq!(s, LONG);
+    assert_eq!(s.kind(), Repr::Static);
+    assert_eq!(s.clone().as_static(), Some(LONG));
+}
+
+#[test]
+fn try_new_too_long() {
+    let err = Buf16::try_new(&[0; 16]).unwrap_err();
+    assert_eq!(err.requested_len(), 16);
+    assert!(Buf16::try_new(&[0; 15]).is_ok());
+
+    let err = Str24::try_new(LONG).unwrap_err();
+    assert_eq!(err.requested_len(), LONG.len());
+    assert!(Buf24::try_new(&[0; 23]).is_ok());
+}
+
+#[test]
+fn slice_static() {
+    let s = Str24::new_static(LONG);
+    let sub = s.slice(5..30);
+    assert_eq!(sub, &LONG[5..30]);
+    assert_eq!(sub.kind(), Repr::Static);
+    assert_eq!(s.slice(5..11), "string");
+    assert_eq!(s.slice(5..11).kind(), Repr::Inline);
+}
+
+#[test]
+fn convert() {
+    let s24 = Str24::from(Str16::new_static(LONG));
+    assert_eq!(s24.as_static(), Some(LONG));
+
+    let s16 = Str16::try_from(Str24::new_inline("fits into Str16")).unwrap();
+    assert_eq!(s16, "fits into Str16");
+
+    let too_long = Str24::new_inline("does not fit into Str16");
+    let err = Str16::try_from(too_long.clone()).unwrap_err();
+    assert_eq!(err.into_inner(), too_long);
+}
+
+#[test]
+fn str8_static() {
+    static TEXT: &str = LONG;
+    let s = Str8::new_static(&TEXT);
+    assert_eq!(s, LONG);
+    assert_eq!(s.kind(), Repr::Static);
+    assert_eq!(Str24::from(s).as_static(), Some(LONG));
+
+    assert!(Str8::try_new("too long").is_err());
+}
@@ -0,0 +1,134 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::HashMap;
+
+use proptest::{prop_assert, prop_assert_eq, proptest};
+
+use smol_buf::{PackedStr24, Repr, Str24};
+
+#[test]
+fn packed_str24_is_smol() {
+    assert_eq!(::std::mem::size_of::<PackedStr24>(), 24);
+    assert_eq!(::std::mem::size_of::<Option<PackedStr24>>(), 24);
+}
+
+#[test]
+fn assert_traits() {
+    fn f<T: Send + Sync + ::std::fmt::Debug + Clone + Ord + ::std::hash::Hash>() {}
+    f::<PackedStr24>();
+}
+
+#[test]
+fn packs_identifiers() {
+    let text = "Zz_9.a-long.identifier_name-1";
+    let s = PackedStr24::new(text);
+    assert!(s.is_packed());
+    assert_eq!(s.kind(), Repr::Inline);
+    assert!(!s.is_heap_allocated());
+    assert_eq!(s.len(), text.len());
+    assert_eq!(s.to_str(&mut [0; PackedStr24::PACKED_CAP]), text);
+    assert_eq!(s.as_unpacked(), None);
+    assert_eq!(s, text);
+    assert_eq!(s.to_string(), text);
+    assert_eq!(format!("{s:?}"), format!("{text:?}"));
+
+    let full = "zzzzzzzzzzzzzzzzzzzzzzzzzzzzzz";
+    assert_eq!(PackedStr24::new(full), full);
+    assert!(PackedStr24::new(full).is_packed());
+
+    let short = PackedStr24::new("short_identifier");
+    assert!(!short.is_packed());
+    assert_eq!(short.as_unpacked(), Some("short_identifier"));
+
+    for text in [
+        "an identifier with spaces!",
+        "a_much_longer_identifier_than_thirty_chars",
+        "ünïcödé_identifier_which_is",
+    ] {
+        let s = PackedStr24::new(text);
+        assert!(!s.is_packed());
+        assert!(s.is_heap_allocated());
+        assert_eq!(s, text);
+    }
+}
+
+#[test]
+fn conversions() {
+    let text = "some_identifier.in_a_module";
+    let s = PackedStr24::from(Str24::new(text));
+    assert!(s.is_packed());
+    let s = Str24::from(s);
+    assert_eq!(s, text);
+    assert_eq!(s.kind(), Repr::Heap);
+
+    let s: PackedStr24 = String::from(text).into();
+    assert!(s.is_packed());
+    assert_eq!(String::from(s), text);
+
+    let s = PackedStr24::new_static("some_identifier.in_a_module");
+    assert!(!s.is_packed());
+    assert_eq!(s.kind(), Repr::Static);
+    assert_eq!(s, PackedStr24::new(text));
+}
+
+#[test]
+fn test_ordering_and_hashing() {
+    let mut strings = [
+        "identifier_zz_aaaaaaaaaaaaaa",
+        "identifier_aa_zzzzzzzzzzzzzz",
+        "identifier_aa_zzzzzzzzzzzzz",
+        "identifier with spaces in it",
+        "IDENTIFIER_AA_ZZZZZZZZZZZZZZ",
+        "short",
+    ];
+    let mut packed: Vec<PackedStr24> = strings.iter().map(PackedStr24::new).collect();
+    strings.sort();
+    packed.sort();
+    assert_eq!(packed, strings);
+
+    let map: HashMap<PackedStr24, usize> = packed
+        .iter()
+        .cloned()
+        .enumerate()
+        .map(|(i, s)| (s, i))
+        .collect();
+    for (i, text) in strings.iter().enumerate() {
+        assert_eq!(map[&PackedStr24::new(text)], i);
+    }
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(s in "[a-zA-Z0-9_.-]{0,32}") {
+        let packed = PackedStr24::new(&s);
+        prop_assert_eq!(packed.is_packed(), (24..=30).contains(&s.len()));
+        let mut buf = [0; PackedStr24::PACKED_CAP];
+        prop_assert_eq!(packed.to_str(&mut buf), s.as_str());
+        prop_assert_eq!(packed.len(), s.len());
+        prop_assert!(packed == PackedStr24::new(&s));
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ordering(a in "[a-c.-]{20,32}", b in "[a-c.-]{20,32}") {
+        let (x, y) = (PackedStr24::new(&a), PackedStr24::new(&b));
+        prop_assert_eq!(x.cmp(&y), a.cmp(&b));
+        prop_assert_eq!(x == y, a == b);
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_serde() {
+        let s = PackedStr24::new("a_packed_identifier_name");
+        let s = serde_json::to_string(&s).unwrap();
+        assert_eq!(s, "\"a_packed_identifier_name\"");
+        let s: PackedStr24 = serde_json::from_str(&s).unwrap();
+        assert!(s.is_packed());
+        assert_eq!(s, "a_packed_identifier_name");
+    }
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use proptest::{prop_assert, prop_assert_eq, proptest};
+
+use smol_buf::{PrefixStr16, Repr, Str16};
+
+#[test]
+fn prefix_str16_is_smol() {
+    assert_eq!(::std::mem::size_of::<PrefixStr16>(), 16);
+}
+
+#[test]
+fn assert_traits() {
+    fn f<T: Send + Sync + ::std::fmt::Debug + Clone>() {}
+    f::<PrefixStr16>();
+}
+
+#[test]
+fn conversions() {
+    let s: PrefixStr16 = "Hello, World!".into();
+    assert_eq!(s.kind(), Repr::Heap);
+    let s: String = s.into();
+    assert_eq!(s, "Hello, World!");
+
+    let s: PrefixStr16 = Arc::<str>::from("Hello, World!").into();
+    let s: Arc<str> = s.into();
+    assert_eq!(s.as_ref(), "Hello, World!");
+
+    let heap = Str16::try_new("a string which is too long to be stored inline").unwrap();
+    let ptr = heap.as_ptr();
+    let s = PrefixStr16::from(heap);
+    assert_eq!(s.as_ptr(), ptr);
+    let s = Str16::from(s);
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s.strong_count(), Some(1));
+
+    let s = PrefixStr16::from(Str16::new("fifteen bytes!!"));
+    assert_eq!(s.kind(), Repr::Heap);
+    assert_eq!(s, "fifteen bytes!!");
+
+    let mut s = Str16::from(PrefixStr16::new("0123456789abcd"));
+    assert_eq!(s.kind(), Repr::Inline);
+    s.push('!');
+    assert_eq!(s, "0123456789abcd!");
+}
+
+#[test]
+fn const_fn_ctor() {
+    const EMPTY: PrefixStr16 = PrefixStr16::new_inline("");
+    const A: PrefixStr16 = PrefixStr16::new_inline("A");
+    const LONG: PrefixStr16 = PrefixStr16::new_inline("ABCDEFGHIJKL");
+    const STATIC: PrefixStr16 = PrefixStr16::new_static("a long static string");
+
+    assert_eq!(EMPTY, PrefixStr16::from(""));
+    assert_eq!(A, PrefixStr16::from("A"));
+    assert_eq!(LONG, PrefixStr16::from("ABCDEFGHIJKL"));
+    assert_eq!(LONG.kind(), Repr::Inline);
+    assert_eq!(STATIC.kind(), Repr::Static);
+    assert_eq!(STATIC.as_static(), Some("a long static string"));
+    assert_eq!(STATIC, PrefixStr16::from("a long static string"));
+}
+
+#[test]
+fn test_introspection() {
+    let s = PrefixStr16::new("a string which is too long to be stored inline");
+    assert!(s.is_heap_allocated());
+    assert_eq!(s.strong_count(), Some(1));
+    let clone = s.clone();
+    assert!(s.ptr_eq(&clone));
+    assert_eq!(s.strong_count(), Some(2));
+    drop(clone);
+    assert_eq!(s.strong_count(), Some(1));
+
+    let short = PrefixStr16::new("short");
+    assert!(!short.is_heap_allocated());
+    assert!(!short.ptr_eq(&short.clone()));
+    assert_eq!(short.strong_count(), None);
+}
+
+#[test]
+fn test_ordering() {
+    let mut strings = [
+        "b",
+        "a\0",
+        "a",
+        "",
+        "abc\0",
+        "abc",
+        "abcd",
+        "abcdefghijklmnop",
+        "abcdefghijklmnoq",
+        "abce",
+        "ab",
+        "\u{ff}",
+    ];
+    let mut prefixed: Vec<PrefixStr16> = strings.iter().map(PrefixStr16::new).collect();
+    strings.sort();
+    prefixed.sort();
+    assert_eq!(prefixed, strings);
+}
+
+#[test]
+fn test_hash_map() {
+    let mut map = HashMap::new();
+    map.insert(PrefixStr16::new("a string which is too long"), 1);
+    map.insert(PrefixStr16::new("short"), 2);
+    assert_eq!(map.get("a string which is too long"), Some(&1));
+    assert_eq!(map.get("short"), Some(&2));
+    assert_eq!(map.get("missing"), None);
+}
+
+fn check_props(
+    std_str: &str,
+    smol: PrefixStr16,
+) -> Result<(), proptest::test_runner::TestCaseError> {
+    prop_assert_eq!(smol.as_str(), std_str);
+    prop_assert_eq!(smol.len(), std_str.len());
+    prop_assert_eq!(smol.is_empty(), std_str.is_empty());
+    prop_assert_eq!(smol.is_heap_allocated(), smol.len() > 12);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(s: String) {
+        check_props(s.as_str(), PrefixStr16::new(s.clone()))?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn ord_and_eq_match_str(a in "[ab\\x00]{0,20}", b in "[ab\\x00]{0,20}") {
+        let (x, y) = (PrefixStr16::new(&a), PrefixStr16::new(&b));
+        prop_assert_eq!(x.cmp(&y), a.cmp(&b));
+        prop_assert_eq!(x == y, a == b);
+        prop_assert!(x == PrefixStr16::new(&a));
+    }
+}
+
+#[cfg(feature = "serde")]
+mod serde_tests {
+    use super::*;
+
+    #[test]
+    fn test_serde() {
+        let s = PrefixStr16::new("Hello, World! This is long");
+        let s = serde_json::to_string(&s).unwrap();
+        assert_eq!(s, "\"Hello, World! This is long\"");
+        let s: PrefixStr16 = serde_json::from_str(&s).unwrap();
+        assert_eq!(s, "Hello, World! This is long");
+    }
+}
@@ -1,8 +1,10 @@
+#![cfg(feature = "alloc")]
+
 use std::sync::Arc;
 
 use proptest::{prop_assert, prop_assert_eq, proptest};
 
-use smol_buf::Str16;
+use smol_buf::{format_str16, Repr, Str16, Str16Builder, ToStr16};
 
 #[test]
 #[cfg(target_pointer_width = "64")]
@@ -86,6 +88,29 @@ proptest! {
         check_props(string.as_str(), smol)?;
     }
 
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn builder(slices in proptest::collection::vec(".*", 1..100)) {
+        let string: String = slices.iter().map(|x| x.as_str()).collect();
+        let mut builder = Str16Builder::new();
+        for slice in &slices {
+            builder.push_str(slice);
+        }
+        check_props(string.as_str(), builder.finish())?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn push_str(slices in proptest::collection::vec(".*", 1..10)) {
+        let mut string = String::new();
+        let mut smol = Str16::default();
+        for slice in &slices {
+            string.push_str(slice);
+            smol.push_str(slice);
+            check_props(string.as_str(), smol.clone())?;
+        }
+    }
+
     #[test]
     #[cfg_attr(miri, ignore)]
     fn from_str_iter(slices in proptest::collection::vec(".*", 1..100)) {
@@ -247,3 +272,175 @@ fn test_bad_size_hint_char_iter() {
     assert!(!new.is_heap_allocated());
     assert_eq!(new, collected);
 }
+
+#[test]
+fn test_slice() {
+    let url = Str16::new("https://example.com/some/long/path/component?query");
+    let path = url.slice(20..44);
+    assert_eq!(path, "some/long/path/component");
+    assert!(path.is_heap_allocated());
+    assert!(std::ptr::eq(path.as_ptr(), url[20..].as_ptr()));
+
+    let short = path.slice(..4);
+    assert_eq!(short, "some");
+    assert!(!short.is_heap_allocated());
+
+    // the slice keeps the parent allocation alive
+    drop(url);
+    assert_eq!(path.slice(5..), "long/path/component");
+    let arc: Arc<str> = path.clone().into();
+    assert_eq!(arc.as_ref(), path.as_str());
+
+    let s = Str16::new_static("a static string which is not inline");
+    let sub = s.slice(2..);
+    assert_eq!(sub, "static string which is not inline");
+    assert!(!sub.is_heap_allocated());
+    assert!(std::ptr::eq(sub.as_ptr(), s[2..].as_ptr()));
+}
+
+#[test]
+#[should_panic]
+fn test_slice_char_boundary() {
+    Str16::new("パーティーへ行かないか").slice(1..);
+}
+
+#[test]
+fn test_builder() {
+    use std::fmt::Write;
+
+    let mut builder = Str16Builder::new();
+    builder.push('i');
+    builder.push_str("d-");
+    write!(builder, "{}", 42).unwrap();
+    let s = builder.finish();
+    assert_eq!(s, "id-42");
+    assert!(!s.is_heap_allocated());
+
+    let mut builder = Str16Builder::default();
+    for i in 0..10 {
+        write!(builder, "{i}-").unwrap();
+    }
+    builder.push('ä');
+    let s: Str16 = builder.into();
+    assert_eq!(s, "0-1-2-3-4-5-6-7-8-9-ä");
+    assert_eq!(s.is_heap_allocated(), s.len() > 15);
+}
+
+#[test]
+fn test_format() {
+    let s = format_str16!("id-{}", 42);
+    assert_eq!(s, "id-42");
+    assert!(!s.is_heap_allocated());
+
+    let s = format_str16!("{:?} is rather long", "this string");
+    assert_eq!(s, "\"this string\" is rather long");
+    assert!(s.is_heap_allocated());
+
+    assert_eq!(42.to_str16(), "42");
+    assert_eq!("str".to_str16(), "str");
+    assert_eq!('c'.to_str16(), "c");
+}
+
+#[test]
+fn test_mutation() {
+    let mut s = Str16::new("ab");
+    s.push('c');
+    s.insert_str(1, "-");
+    assert_eq!(s, "a-bc");
+    s.make_mut().make_ascii_uppercase();
+    assert_eq!(s, "A-BC");
+    assert!(!s.is_heap_allocated());
+
+    s.push_str(&"x".repeat(15));
+    assert!(s.is_heap_allocated());
+    assert_eq!(s.len(), 15 + 4);
+
+    // shared strings are copied before mutation
+    let shared = s.clone();
+    s.make_mut().make_ascii_lowercase();
+    assert_eq!(shared, format!("A-BC{}", "x".repeat(15)));
+    assert_eq!(s, format!("a-bc{}", "x".repeat(15)));
+
+    // unique strings are mutated in place
+    let ptr = s.as_ptr();
+    s.make_mut().make_ascii_uppercase();
+    assert_eq!(s.as_ptr(), ptr);
+
+    // truncating keeps sharing the allocation
+    s.truncate(15 + 2);
+    assert_eq!(s.as_ptr(), ptr);
+    assert_eq!(s, format!("A-BC{}", "X".repeat(15 - 2)));
+    s.truncate(2);
+    assert_eq!(s, "A-");
+    assert!(!s.is_heap_allocated());
+
+    let mut s = Str16::new_static("a static string which is not inline");
+    s.make_mut().make_ascii_uppercase();
+    assert_eq!(s, "A STATIC STRING WHICH IS NOT INLINE");
+    assert!(s.is_heap_allocated());
+    s.clear();
+    assert_eq!(s, "");
+}
+
+#[test]
+#[should_panic]
+fn test_truncate_char_boundary() {
+    Str16::new("パーティー").truncate(1);
+}
+
+#[test]
+fn test_introspection() {
+    let inline = Str16::new("smol");
+    assert_eq!(inline.kind(), Repr::Inline);
+    assert_eq!(inline.strong_count(), None);
+    assert_eq!(inline.as_static(), None);
+    assert!(!inline.ptr_eq(&inline.clone()));
+
+    let text = "a static string which is not inline";
+    let s = Str16::new_static(text);
+    assert_eq!(s.kind(), Repr::Static);
+    assert_eq!(s.strong_count(), None);
+    assert!(std::ptr::eq(s.as_static().unwrap(), text));
+    assert!(s.ptr_eq(&s.clone()));
+    assert_eq!(Str16::new_static("smol").as_static(), None);
+
+    let heap = Str16::new(text);
+    assert_eq!(heap.kind(), Repr::Heap);
+    assert_eq!(heap.as_static(), None);
+    assert_eq!(heap.strong_count(), Some(1));
+    let clone = heap.clone();
+    let slice = heap.slice(1..);
+    assert_eq!(heap.strong_count(), Some(3));
+    assert!(heap.ptr_eq(&clone));
+    assert!(!heap.ptr_eq(&slice));
+    assert!(!heap.ptr_eq(&s));
+    drop(clone);
+    drop(heap);
+    assert_eq!(slice.strong_count(), Some(1));
+}
+
+#[test]
+fn test_try_new() {
+    let s = Str16::try_from_str("smol").unwrap();
+    assert_eq!(s, "smol");
+    assert_eq!(s.kind(), Repr::Inline);
+
+    let text = "a string which is too long to be stored inline";
+    let s = Str16::try_new(text).unwrap();
+    assert_eq!(s, text);
+    assert_eq!(s.kind(), Repr::Heap);
+    assert_eq!(s.slice(2..8), "string");
+    assert_eq!(s.slice(2..30), &text[2..30]);
+
+    let s = Str16::try_from_iter(text.chars().rev()).unwrap();
+    assert_eq!(s, text.chars().rev().collect::<String>());
+
+    let mut builder = Str16Builder::new();
+    builder.try_push_str("smol ").unwrap();
+    builder.try_push_str("and then some more").unwrap();
+    builder.try_push_str(" text").unwrap();
+    assert_eq!(
+        builder.try_finish().unwrap(),
+        "smol and then some more text"
+    );
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "alloc")]
+
+use proptest::{prop_assert, prop_assert_eq, proptest};
+
+use smol_buf::{format_str32, Repr, Str24, Str32, Str32Builder, ToStr32};
+
+#[test]
+fn str32_is_smol() {
+    assert_eq!(::std::mem::size_of::<Str32>(), 32);
+    assert_eq!(::std::mem::size_of::<Option<Str32>>(), 32);
+}
+
+#[test]
+fn assert_traits() {
+    fn f<T: Send + Sync + ::std::fmt::Debug + Clone + Ord + ::std::hash::Hash>() {}
+    f::<Str32>();
+}
+
+#[test]
+fn const_fn_ctor() {
+    const EMPTY: Str32 = Str32::new_inline("");
+    const LONG: Str32 = Str32::new_inline("ABCDEFGHIZKLMNOPQRSTUVWXYZ01234");
+
+    assert_eq!(EMPTY, Str32::from(""));
+    assert_eq!(LONG, Str32::from("ABCDEFGHIZKLMNOPQRSTUVWXYZ01234"));
+    assert_eq!(LONG.kind(), Repr::Inline);
+}
+
+fn check_props(std_str: &str, smol: Str32) -> Result<(), proptest::test_runner::TestCaseError> {
+    prop_assert_eq!(smol.as_str(), std_str);
+    prop_assert_eq!(smol.len(), std_str.len());
+    prop_assert_eq!(smol.is_empty(), std_str.is_empty());
+    if smol.len() <= 31 {
+        prop_assert!(!smol.is_heap_allocated());
+    }
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(s: String) {
+        check_props(s.as_str(), Str32::new(s.clone()))?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn builder(slices in proptest::collection::vec(".*", 1..100)) {
+        let mut builder = Str32Builder::new();
+        for s in &slices {
+            builder.push_str(s);
+        }
+        check_props(&slices.concat(), builder.finish())?;
+    }
+}
+
+#[test]
+fn test_identifiers() {
+    // a UUID without hyphens still does not fit
+    let uuid = "67e5504410b1426f9247bb680e5fe0c8";
+    assert!(Str32::new(uuid).is_heap_allocated());
+    assert!(!Str32::new(&uuid[1..]).is_heap_allocated());
+    assert!(Str24::new(&uuid[1..]).is_heap_allocated());
+}
+
+#[test]
+fn test_format() {
+    let s = format_str32!("{}-{}", "an-identifier", 1234567890);
+    assert_eq!(s, "an-identifier-1234567890");
+    assert!(!s.is_heap_allocated());
+    assert_eq!(42.to_str32(), "42");
+}
+
+#[test]
+fn test_slice() {
+    let text = "a".repeat(100);
+    let s = Str32::new(&text);
+    let sub = s.slice(10..60);
+    assert_eq!(sub, &text[10..60]);
+    assert!(std::ptr::eq(sub.as_ptr(), s[10..].as_ptr()));
+    assert_eq!(s.slice(..31).kind(), Repr::Inline);
+}
+
+#[test]
+fn test_convert() {
+    let text = "a string which is too long to be stored inline";
+    let s24 = Str24::new(text);
+    let s32 = Str32::from(s24.clone());
+    assert!(s32.ptr_eq(&Str32::from(s24.clone())));
+    assert_eq!(s32.strong_count(), Some(2));
+
+    let back = Str24::try_from(s32).unwrap();
+    assert!(back.ptr_eq(&s24));
+
+    let inline = Str32::new_inline("this fits inline, but not Str24");
+    let s24 = Str24::try_from(inline).unwrap();
+    assert_eq!(s24, "this fits inline, but not Str24");
+    assert_eq!(s24.kind(), Repr::Heap);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_serde() {
+    let s = Str32::new("Hello, World");
+    let json = serde_json::to_string(&s).unwrap();
+    assert_eq!(json, "\"Hello, World\"");
+    let s: Str32 = serde_json::from_str(&json).unwrap();
+    assert_eq!(s, "Hello, World");
+    assert!(!s.is_heap_allocated());
+}
@@ -0,0 +1,140 @@
+#![cfg(feature = "alloc")]
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use proptest::{prop_assert_eq, proptest};
+
+use smol_buf::{Repr, Str16, Str24, Str8, Str8Builder};
+
+#[test]
+fn str8_is_smol() {
+    assert_eq!(::std::mem::size_of::<Str8>(), 8);
+    assert_eq!(::std::mem::size_of::<Option<Str8>>(), 8);
+}
+
+#[test]
+fn assert_traits() {
+    fn f<T: Send + Sync + ::std::fmt::Debug + Clone>() {}
+    f::<Str8>();
+}
+
+#[test]
+fn conversions() {
+    let s: Str8 = "Hello, World!".into();
+    let s: String = s.into();
+    assert_eq!(s, "Hello, World!");
+
+    let s: Str8 = Arc::<str>::from("Hello, World!").into();
+    let s: Arc<str> = s.into();
+    assert_eq!(s.as_ref(), "Hello, World!");
+}
+
+#[test]
+fn const_fn_ctor() {
+    const EMPTY: Str8 = Str8::new_inline("");
+    const A: Str8 = Str8::new_inline("A");
+    const LONG: Str8 = Str8::new_inline("ABCDEFG");
+
+    assert_eq!(EMPTY, Str8::from(""));
+    assert_eq!(A, Str8::from("A"));
+    assert_eq!(LONG, Str8::from("ABCDEFG"));
+}
+
+fn check_props(std_str: &str, smol: Str8) -> Result<(), proptest::test_runner::TestCaseError> {
+    prop_assert_eq!(smol.as_str(), std_str);
+    prop_assert_eq!(smol.len(), std_str.len());
+    prop_assert_eq!(smol.is_empty(), std_str.is_empty());
+    prop_assert_eq!(smol.is_heap_allocated(), smol.len() > 7);
+    Ok(())
+}
+
+proptest! {
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn roundtrip(s: String) {
+        check_props(s.as_str(), Str8::new(s.clone()))?;
+    }
+
+    #[test]
+    #[cfg_attr(miri, ignore)]
+    fn builder(slices in proptest::collection::vec(".*", 1..100)) {
+        let mut builder = Str8Builder::new();
+        for s in &slices {
+            builder.push_str(s);
+        }
+        check_props(&slices.concat(), builder.finish())?;
+    }
+}
+
+#[test]
+fn test_search_in_hashmap() {
+    let mut m = HashMap::<Str8, i32>::new();
+    m.insert("a".into(), 17);
+    m.insert("a long enum-like tag".into(), 18);
+    assert_eq!(17, *m.get("a").unwrap());
+    assert_eq!(18, *m.get("a long enum-like tag").unwrap());
+}
+
+#[test]
+fn test_static() {
+    static LONG: &str = "a long static string";
+    let s = Str8::new_static(&LONG);
+    assert_eq!(s, LONG);
+    assert_eq!(s.kind(), Repr::Static);
+    assert_eq!(s.strong_count(), None);
+    assert!(std::ptr::eq(s.clone().as_static().unwrap(), LONG));
+
+    let s16 = Str16::from(s);
+    assert_eq!(s16.kind(), Repr::Static);
+    assert!(std::ptr::eq(s16.as_static().unwrap(), LONG));
+
+    let s = Str8::new_static(&"smol");
+    assert_eq!(s.kind(), Repr::Inline);
+    assert_eq!(s.as_static(), None);
+}
+
+#[test]
+fn test_mutation() {
+    let mut s = Str8::new("ab");
+    s.push('c');
+    s.insert_str(1, "-");
+    assert_eq!(s, "a-bc");
+    assert!(!s.is_heap_allocated());
+
+    s.push_str("defgh");
+    assert!(s.is_heap_allocated());
+    assert_eq!(s, "a-bcdefgh");
+
+    let shared = s.clone();
+    s.make_mut().make_ascii_uppercase();
+    assert_eq!(shared, "a-bcdefgh");
+    assert_eq!(s, "A-BCDEFGH");
+    assert_eq!(s.strong_count(), Some(1));
+
+    s.truncate(8);
+    assert_eq!(s, "A-BCDEFG");
+    assert_eq!(s.slice(2..), "BCDEFG");
+    assert_eq!(s.slice(2..).kind(), Repr::Inline);
+}
+
+#[test]
+fn test_convert() {
+    let text = "a string which does not fit inline";
+    let s8 = Str8::new(text);
+    let s24 = Str24::from(s8.clone());
+    assert_eq!(s24, text);
+    assert_eq!(s24.strong_count(), Some(2));
+    assert!(s24.as_ptr() == s8.as_ptr());
+
+    let s16 = Str16::from(s8);
+    assert_eq!(s16, text);
+    let s8 = Str8::from(s16);
+    assert_eq!(s8, text);
+    assert_eq!(s8.strong_count(), Some(2));
+
+    // strings backed by an `Arc` are copied
+    let s8 = Str8::from(Str24::new(text));
+    assert_eq!(s8, text);
+    assert_eq!(s8.strong_count(), Some(1));
+}